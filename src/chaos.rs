@@ -0,0 +1,74 @@
+use crate::mover::Mover;
+
+/// Distance the shadow starts from the system, in phase space. Large enough that rounding
+/// the positions to f32 every tick doesn't swamp it.
+const SEPARATION: f64 = 1e-2;
+/// Ticks between two renormalizations of the shadow.
+const RENORMALIZE_TICKS: u32 = 10;
+
+/// A copy of the system started `SEPARATION` away from it. How fast the two drift apart
+/// measures the sensitivity to initial conditions, the finite-time Lyapunov exponent. The
+/// shadow is pulled back to the starting separation every few ticks so that the
+/// divergence keeps growing exponentially instead of saturating (Benettin's method).
+pub struct Shadow {
+    pub planets: Vec<Mover>,
+    /// Sum of ln(d / d0) over the finished renormalization intervals.
+    log_growth: f64,
+    ticks: u32,
+    elapsed: f64
+}
+
+impl Shadow {
+    pub fn new(planets: &[Mover]) -> Self {
+        let mut shadow = planets.to_vec();
+        if let Some(planet) = shadow.iter_mut().find(|p| p.apply_forces && !p.frozen) {
+            planet.pos.x += SEPARATION as f32;
+        }
+
+        Self {
+            planets: shadow,
+            log_growth: 0.,
+            ticks: 0,
+            elapsed: 0.
+        }
+    }
+
+    /// Phase space distance between the shadow and the system.
+    fn separation(&self, planets: &[Mover]) -> f64 {
+        planets.iter().zip(&self.planets).filter(|(p, _)| p.apply_forces && !p.frozen)
+            .map(|(p, s)| (s.pos - p.pos).as_dvec2().length_squared() + (s.vel - p.vel).as_dvec2().length_squared())
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Total growth ln(d / d0) of the separation since the shadow was started.
+    pub fn divergence(&self, planets: &[Mover]) -> Option<f64> {
+        let separation = self.separation(planets);
+        (separation > 0.).then(|| self.log_growth + (separation / SEPARATION).ln())
+    }
+
+    /// Finite-time Lyapunov exponent, per time unit.
+    pub fn exponent(&self, planets: &[Mover]) -> Option<f64> {
+        self.divergence(planets).filter(|_| self.elapsed > 0.).map(|d| d / self.elapsed)
+    }
+
+    /// Call once both the system and the shadow were stepped.
+    pub fn stepped(&mut self, planets: &[Mover]) {
+        self.elapsed += 1.;
+        self.ticks += 1;
+        if self.ticks < RENORMALIZE_TICKS {
+            return;
+        }
+        self.ticks = 0;
+
+        let separation = self.separation(planets);
+        if separation > 0. {
+            self.log_growth += (separation / SEPARATION).ln();
+            let scale = (SEPARATION / separation) as f32;
+            for (shadow, planet) in self.planets.iter_mut().zip(planets) {
+                shadow.pos = planet.pos + (shadow.pos - planet.pos) * scale;
+                shadow.vel = planet.vel + (shadow.vel - planet.vel) * scale;
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,146 @@
+use std::io;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs::File, io::{BufWriter, Write}};
+
+use notan::math::DVec2;
+
+use crate::{mover::Mover, integrator::Integrator, regularization, G};
+
+/// Conserved quantities of the whole system. Bodies that ignore forces count as standing still,
+/// frozen bodies don't count at all.
+#[derive(Clone, Copy, Default)]
+pub struct Diagnostics {
+    pub kinetic: f64,
+    pub potential: f64,
+    pub momentum: DVec2,
+    pub angular_momentum: f64
+}
+
+impl Diagnostics {
+    /// Measures the system with the same force law the integrator uses, so that a perfect
+    /// integrator would keep the total energy constant.
+    pub fn measure(planets: &[Mover], integrator: Integrator, softening: f32, regularization: Option<f32>) -> Self {
+        let eps = softening as f64;
+        let moving = planets.iter().map(|p| p.apply_forces).collect::<Vec<_>>();
+        let mass = planets.iter().map(|p| if p.frozen { 0. } else { p.m as f64 }).collect::<Vec<_>>();
+        let pos = planets.iter().map(|p| p.pos.as_dvec2()).collect::<Vec<_>>();
+        let vel = planets.iter().map(|p| if p.apply_forces { p.vel.as_dvec2() } else { DVec2::ZERO }).collect::<Vec<_>>();
+
+        // Regularized pairs attract each other without softening
+        let pairs = match (integrator, regularization) {
+            (Integrator::Hermite, Some(radius)) => regularization::find_pairs(&pos, &vel, &mass, &moving, radius as f64, eps * eps),
+            _ => Vec::new()
+        };
+
+        let mut diagnostics = Diagnostics::default();
+        for i in 0..planets.len() {
+            diagnostics.kinetic += mass[i] * vel[i].length_squared() / 2.;
+            diagnostics.momentum += vel[i] * mass[i];
+            diagnostics.angular_momentum += mass[i] * pos[i].perp_dot(vel[i]);
+
+            for j in i + 1..planets.len() {
+                let gmm = G as f64 * mass[i] * mass[j];
+                let r = pos[i].distance(pos[j]);
+
+                diagnostics.potential += if pairs.iter().any(|pair| pair.i == i && pair.j == j) {
+                    -gmm / r
+                } else {
+                    pair_potential(integrator, gmm, r, eps)
+                };
+            }
+        }
+
+        diagnostics
+    }
+
+    pub fn energy(&self) -> f64 {
+        self.kinetic + self.potential
+    }
+}
+
+/// Potential energy of two bodies `r` apart, `gmm` is `G * m1 * m2`. The Euler law is the
+/// one of `Mover::attract`.
+pub fn pair_potential(integrator: Integrator, gmm: f64, r: f64, eps: f64) -> f64 {
+    match integrator {
+        // The Euler force is clamped to a constant below the softening length and beyond
+        // 50 units, where the potential turns into a straight line.
+        Integrator::Euler => {
+            let far = 50.;
+            if r < eps {
+                -gmm / eps + gmm * (r - eps) / (eps * eps)
+            } else if r > far {
+                -gmm / far + gmm * (r - far) / (far * far)
+            } else {
+                -gmm / r
+            }
+        }
+        Integrator::Hermite | Integrator::Leapfrog => -gmm / (r * r + eps * eps).sqrt()
+    }
+}
+
+const LOG_HEADER: &str = "time,kinetic,potential,energy,drift,momentum_x,momentum_y,angular_momentum";
+
+/// Appends diagnostics to a CSV file. The web build has no files, so the rows are kept
+/// until they are saved as a download.
+pub struct DiagnosticsLog {
+    pub path: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    file: BufWriter<File>,
+    #[cfg(target_arch = "wasm32")]
+    rows: String,
+    last_time: Option<f64>
+}
+
+impl DiagnosticsLog {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn create(path: &str) -> io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "{}", LOG_HEADER)?;
+
+        Ok(Self {
+            path: path.to_string(),
+            file,
+            last_time: None
+        })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn create(path: &str) -> io::Result<Self> {
+        Ok(Self {
+            path: path.to_string(),
+            rows: format!("{}\n", LOG_HEADER),
+            last_time: None
+        })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn to_csv(&self) -> &str {
+        &self.rows
+    }
+
+    /// Writes a row, unless one was already written for this simulated time.
+    pub fn write(&mut self, time: f64, diagnostics: &Diagnostics, drift: f64) -> io::Result<()> {
+        if self.last_time == Some(time) {
+            return Ok(());
+        }
+        self.last_time = Some(time);
+
+        let d = diagnostics;
+        let row = format!("{},{},{},{},{},{},{},{}", time, d.kinetic, d.potential, d.energy(), drift, d.momentum.x, d.momentum.y, d.angular_momentum);
+        self.append(&row)
+    }
+
+    // Notan doesn't drop the state when the window is closed, so nothing may wait in the buffer
+    #[cfg(not(target_arch = "wasm32"))]
+    fn append(&mut self, row: &str) -> io::Result<()> {
+        writeln!(self.file, "{}", row)?;
+        self.file.flush()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn append(&mut self, row: &str) -> io::Result<()> {
+        self.rows.push_str(row);
+        self.rows.push('\n');
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,160 @@
+use std::{collections::{HashMap, VecDeque}, fmt::Write};
+
+use notan::math::Vec2;
+
+use crate::{mover::Mover, integrator::Integrator, orbit, simulation::EscapePolicy};
+
+#[derive(Clone, Copy)]
+pub enum EventKind {
+    /// Two bodies came closer than the approach distance, reported once they separate again.
+    CloseApproach { min_distance: f32, relative_speed: f32 },
+    Periapsis { distance: f32 },
+    Unbound,
+    Escaped(EscapePolicy)
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::CloseApproach { .. } => "closeapproach",
+            EventKind::Periapsis { .. } => "periapsis",
+            EventKind::Unbound => "unbound",
+            EventKind::Escaped(..) => "escaped"
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Event {
+    pub time: f64,
+    pub kind: EventKind,
+    pub body: usize,
+    pub other: Option<usize>
+}
+
+struct Approach {
+    min_distance: f32,
+    relative_speed: f32,
+    time: f64
+}
+
+pub struct EventLog {
+    pub events: VecDeque<Event>,
+    pub capacity: usize,
+    pub approach_distance: f32,
+    approaches: HashMap<(usize, usize), Approach>,
+    radial: Vec<Option<(usize, f32)>>,
+    bound: Vec<bool>
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity: 10000,
+            approach_distance: 20.,
+            approaches: HashMap::new(),
+            radial: Vec::new(),
+            bound: Vec::new()
+        }
+    }
+
+    pub fn clear(&mut self, planets: &[Mover], integrator: Integrator, softening: f32) {
+        self.events.clear();
+        self.resync(planets, integrator, softening);
+    }
+
+    pub fn resync(&mut self, planets: &[Mover], integrator: Integrator, softening: f32) {
+        self.approaches.clear();
+        self.radial = (0..planets.len()).map(|i| radial_velocity(planets, i)).collect();
+        self.bound = (0..planets.len()).map(|i| orbit::bound_to_system(planets, i, integrator, softening)).collect();
+    }
+
+    fn push(&mut self, event: Event) {
+        if self.events.len() >= self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    pub fn detect(&mut self, time: f64, planets: &[Mover], integrator: Integrator, softening: f32) {
+        if self.radial.len() != planets.len() {
+            self.resync(planets, integrator, softening);
+            return;
+        }
+
+        for i in 0..planets.len() {
+            for j in i + 1..planets.len() {
+                let distance = planets[i].pos.distance(planets[j].pos);
+                if distance < self.approach_distance {
+                    let relative_speed = (velocity(&planets[j]) - velocity(&planets[i])).length();
+                    let approach = self.approaches.entry((i, j)).or_insert(Approach { min_distance: f32::INFINITY, relative_speed, time });
+                    if distance < approach.min_distance {
+                        *approach = Approach { min_distance: distance, relative_speed, time };
+                    }
+                } else if let Some(approach) = self.approaches.remove(&(i, j)) {
+                    let kind = EventKind::CloseApproach { min_distance: approach.min_distance, relative_speed: approach.relative_speed };
+                    self.push(Event { time: approach.time, kind, body: planets[i].id, other: Some(planets[j].id) });
+                }
+            }
+        }
+
+        for i in 0..planets.len() {
+            let radial = radial_velocity(planets, i);
+            if let (Some((parent, before)), Some((now_parent, now))) = (self.radial[i], radial) {
+                if parent == now_parent && before < 0. && now >= 0. && orbit::bound_to(planets, i, parent, integrator, softening) {
+                    let distance = planets[i].pos.distance(planets[parent].pos);
+                    self.push(Event { time, kind: EventKind::Periapsis { distance }, body: planets[i].id, other: Some(planets[parent].id) });
+                }
+            }
+            self.radial[i] = radial;
+
+            let bound = orbit::bound_to_system(planets, i, integrator, softening);
+            if self.bound[i] && !bound {
+                self.push(Event { time, kind: EventKind::Unbound, body: planets[i].id, other: None });
+            }
+            self.bound[i] = bound;
+        }
+    }
+
+    pub fn escaped(&mut self, time: f64, id: usize, policy: EscapePolicy) {
+        self.push(Event { time, kind: EventKind::Escaped(policy), body: id, other: None });
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time,event,body,other,distance,relative_speed\n");
+
+        for event in self.events.iter() {
+            let other = event.other.map(|o| o.to_string()).unwrap_or_default();
+            let (distance, speed) = match event.kind {
+                EventKind::CloseApproach { min_distance, relative_speed } => (min_distance.to_string(), relative_speed.to_string()),
+                EventKind::Periapsis { distance } => (distance.to_string(), String::new()),
+                EventKind::Unbound | EventKind::Escaped(..) => (String::new(), String::new())
+            };
+            let name = match event.kind {
+                EventKind::Escaped(policy) => format!("{}:{}", event.kind.name(), policy.name()),
+                _ => event.kind.name().to_string()
+            };
+            let _ = writeln!(csv, "{},{},{},{},{},{}", event.time, name, event.body, other, distance, speed);
+        }
+        csv
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn radial_velocity(planets: &[Mover], i: usize) -> Option<(usize, f32)> {
+    if !planets[i].apply_forces {
+        return None;
+    }
+    let parent = orbit::dominant_attractor(planets, i)?;
+    Some((parent, (planets[i].pos - planets[parent].pos).dot(planets[i].vel - velocity(&planets[parent]))))
+}
+
+fn velocity(planet: &Mover) -> Vec2 {
+    if planet.apply_forces { planet.vel } else { Vec2::ZERO }
+}
\ No newline at end of file
@@ -0,0 +1,139 @@
+use notan::math::Vec2;
+
+use crate::{mover::Mover, integrator::Integrator, diagnostics, G};
+
+const COLORMAP: [[f32; 3]; 5] = [
+    [0.05, 0.03, 0.16],
+    [0.29, 0.05, 0.42],
+    [0.65, 0.17, 0.38],
+    [0.93, 0.41, 0.15],
+    [0.99, 1.0, 0.64]
+];
+const HEATMAP_ALPHA: u8 = 170;
+
+pub fn pair_acceleration(integrator: Integrator, m: f32, d: Vec2, softening: f32) -> Vec2 {
+    let r_sq = d.length_squared();
+    match integrator {
+        Integrator::Euler => d.normalize_or_zero() * G * m / r_sq.clamp(softening * softening, 2500.),
+        Integrator::Hermite | Integrator::Leapfrog => {
+            let soft_sq = r_sq + softening * softening;
+            if soft_sq > 0. { d * G * m / (soft_sq * soft_sq.sqrt()) } else { Vec2::ZERO }
+        }
+    }
+}
+
+pub fn accelerations(planets: &[Mover], i: usize, integrator: Integrator, softening: f32) -> Vec<(usize, Vec2)> {
+    planets.iter().enumerate()
+        .filter(|(j, p)| *j != i && !p.frozen && p.m > 0.)
+        .map(|(j, p)| (j, pair_acceleration(integrator, p.m, p.pos - planets[i].pos, softening)))
+        .collect()
+}
+
+/// Grid pulled towards the bodies, the rubber sheet picture of curved spacetime.
+pub struct WarpedGrid {
+    pub columns: usize,
+    pub rows: usize,
+    pub nodes: Vec<Vec2>
+}
+
+impl WarpedGrid {
+    pub fn new(planets: &[Mover], integrator: Integrator, softening: f32, origin: Vec2, size: Vec2, spacing: f32, strength: f32) -> Self {
+        let start = (origin / spacing).floor() * spacing;
+        let columns = (size.x / spacing).ceil() as usize + 2;
+        let rows = (size.y / spacing).ceil() as usize + 2;
+        let sources = planets.iter().filter(|p| !p.frozen && p.m > 0.).collect::<Vec<_>>();
+
+        let nodes = (0..rows * columns).map(|k| {
+            let node = start + Vec2::new((k % columns) as f32, (k / columns) as f32) * spacing;
+            let acc = sources.iter().map(|p| pair_acceleration(integrator, p.m, p.pos - node, softening)).sum::<Vec2>();
+            let pull = spacing * (1. - (-acc.length() * strength / spacing).exp());
+            node + acc.normalize_or_zero() * pull
+        }).collect();
+
+        Self { columns, rows, nodes }
+    }
+
+    pub fn node(&self, column: usize, row: usize) -> Vec2 {
+        self.nodes[row * self.columns + column]
+    }
+}
+
+pub struct PotentialGrid {
+    pub width: usize,
+    pub height: usize,
+    pub origin: Vec2,
+    pub size: Vec2,
+    depth: Vec<f32>
+}
+
+impl PotentialGrid {
+    pub fn sample(planets: &[Mover], integrator: Integrator, softening: f32, origin: Vec2, size: Vec2, width: usize, height: usize) -> Self {
+        let mut grid = Self { width, height, origin, size, depth: Vec::with_capacity(width * height) };
+
+        let eps = softening as f64;
+        let sources = planets.iter().filter(|p| !p.frozen && p.m > 0.).collect::<Vec<_>>();
+        let potentials = (0..width * height).map(|k| {
+            let point = grid.point((k % width) as f32, (k / width) as f32);
+            sources.iter().map(|p| diagnostics::pair_potential(integrator, G as f64 * p.m as f64, point.distance(p.pos) as f64, eps)).sum::<f64>()
+        }).collect::<Vec<_>>();
+
+        // The Euler law's potential keeps rising past 50 px, so it is measured from the top
+        // of the view instead of from zero, in units of the median depth
+        let top = potentials.iter().copied().filter(|p| p.is_finite()).fold(f64::NEG_INFINITY, f64::max);
+        let depths = potentials.iter().map(|p| top - p).collect::<Vec<_>>();
+        let median = percentile(depths.iter().copied().filter(|d| d.is_finite() && *d > 0.).collect(), 0.5).unwrap_or(1.);
+        let log_depth = depths.iter().map(|d| (d / median).ln_1p()).collect::<Vec<_>>();
+
+        // Bodies at zero softening sit at an infinite depth, the deepest percent is clipped
+        let deepest = percentile(log_depth.iter().copied().filter(|d| d.is_finite()).collect(), 0.99).filter(|&d| d > 0.).unwrap_or(1.);
+        grid.depth = log_depth.iter().map(|d| (d / deepest).clamp(0., 1.) as f32).collect();
+        grid
+    }
+
+    fn point(&self, x: f32, y: f32) -> Vec2 {
+        self.origin + Vec2::new((x + 0.5) / self.width as f32, (y + 0.5) / self.height as f32) * self.size
+    }
+
+    pub fn pixels(&self) -> Vec<u8> {
+        self.depth.iter().flat_map(|&t| {
+            let scaled = t * (COLORMAP.len() - 1) as f32;
+            let i = (scaled as usize).min(COLORMAP.len() - 2);
+            let f = scaled - i as f32;
+            let (a, b) = (COLORMAP[i], COLORMAP[i + 1]);
+            let channel = |c: usize| ((a[c] + (b[c] - a[c]) * f) * 255.) as u8;
+            [channel(0), channel(1), channel(2), HEATMAP_ALPHA]
+        }).collect()
+    }
+
+    /// Equipotential lines traced with marching squares.
+    pub fn contours(&self, levels: usize) -> Vec<(Vec2, Vec2)> {
+        let mut segments = Vec::new();
+        let depth = |x: usize, y: usize| self.depth[y * self.width + x];
+
+        for level in (0..levels).map(|k| (k as f32 + 0.5) / levels as f32) {
+            for y in 0..self.height.saturating_sub(1) {
+                for x in 0..self.width.saturating_sub(1) {
+                    let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+                    let mut crossings = Vec::with_capacity(4);
+                    for edge in 0..4 {
+                        let (a, b) = (corners[edge], corners[(edge + 1) % 4]);
+                        let (da, db) = (depth(a.0, a.1), depth(b.0, b.1));
+                        if (da < level) != (db < level) {
+                            let t = (level - da) / (db - da);
+                            let (ax, ay) = (a.0 as f32, a.1 as f32);
+                            crossings.push(self.point(ax + (b.0 as f32 - ax) * t, ay + (b.1 as f32 - ay) * t));
+                        }
+                    }
+                    for pair in crossings.chunks_exact(2) {
+                        segments.push((pair[0], pair[1]));
+                    }
+                }
+            }
+        }
+        segments
+    }
+}
+fn percentile(mut values: Vec<f64>, q: f64) -> Option<f64> {
+    values.sort_by(f64::total_cmp);
+    values.get(((values.len() as f64 - 1.) * q).round() as usize).copied()
+}
\ No newline at end of file
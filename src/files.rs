@@ -0,0 +1,99 @@
+use std::{cell::RefCell, rc::Rc};
+
+/// Files opened by the user, as name and contents. On the web they arrive once the browser
+/// has read them, so they are collected here and picked up every frame.
+#[derive(Clone, Default)]
+pub struct Uploads(Rc<RefCell<Vec<(String, String)>>>);
+
+impl Uploads {
+    pub fn take(&self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.0.borrow_mut())
+    }
+
+    fn push(&self, name: String, text: String) {
+        self.0.borrow_mut().push((name, text));
+    }
+}
+
+/// Writes `text` to `name` in `dir` and returns where it went.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(dir: &str, name: &str, text: &str) -> Result<String, String> {
+    let path = std::path::Path::new(dir).join(name);
+    std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, text)).map_err(|e| e.to_string())?;
+    Ok(path.display().to_string())
+}
+
+/// Reads `name` from `dir`, an absolute path is read as it is.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open(dir: &str, name: &str, uploads: &Uploads) -> Result<(), String> {
+    let path = std::path::Path::new(dir).join(name);
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    uploads.push(name, text);
+    Ok(())
+}
+
+/// Hands `text` to the browser as a download named `name`.
+#[cfg(target_arch = "wasm32")]
+pub fn save(_dir: &str, name: &str, text: &str) -> Result<String, String> {
+    use wasm_bindgen::JsCast;
+    use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+    let document = web_sys::window().and_then(|w| w.document()).ok_or("no document")?;
+    let options = BlobPropertyBag::new();
+    options.set_type(if name.ends_with(".csv") { "text/csv" } else { "application/json" });
+    let blob = Blob::new_with_str_sequence_and_options(&js_sys::Array::of1(&text.into()), &options).map_err(js_error)?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+
+    let anchor = document.create_element("a").map_err(js_error)?.dyn_into::<HtmlAnchorElement>().map_err(js_error)?;
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+    Url::revoke_object_url(&url).map_err(js_error)?;
+    Ok(name.to_string())
+}
+
+/// Lets the user pick a file in the browser, it shows up in `uploads` once it was read.
+#[cfg(target_arch = "wasm32")]
+pub fn open(_dir: &str, _name: &str, uploads: &Uploads) -> Result<(), String> {
+    use wasm_bindgen::{closure::Closure, JsCast};
+    use web_sys::{FileReader, HtmlInputElement};
+
+    let document = web_sys::window().and_then(|w| w.document()).ok_or("no document")?;
+    let input = document.create_element("input").map_err(js_error)?.dyn_into::<HtmlInputElement>().map_err(js_error)?;
+    input.set_type("file");
+    input.set_accept(".json,application/json");
+
+    let picker = input.clone();
+    let uploads = uploads.clone();
+    let onchange = Closure::once(move || {
+        let Some(file) = picker.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        let Ok(reader) = FileReader::new() else {
+            return;
+        };
+
+        let name = file.name();
+        let result = reader.clone();
+        let onload = Closure::once(move || {
+            if let Some(text) = result.result().ok().and_then(|r| r.as_string()) {
+                uploads.push(name, text);
+            }
+        });
+        reader.set_onload(Some(onload.as_ref().unchecked_ref()));
+        onload.forget();
+        let _ = reader.read_as_text(&file);
+    });
+    input.set_onchange(Some(onchange.as_ref().unchecked_ref()));
+    onchange.forget();
+
+    input.click();
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn js_error(value: impl Into<wasm_bindgen::JsValue>) -> String {
+    let value = value.into();
+    value.as_string().unwrap_or_else(|| format!("{:?}", value))
+}
\ No newline at end of file
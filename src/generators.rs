@@ -0,0 +1,299 @@
+use std::f64::consts::TAU;
+
+use notan::{math::{Vec2, DVec2}, random::{utils::Random, rand::Rng}};
+
+use crate::{mover::Mover, integrator::Integrator, field, vec_math, G};
+
+/// Plummer positions are drawn again beyond this many scale radii.
+const PLUMMER_CUTOFF: f64 = 5.;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Generator {
+    Plummer,
+    King,
+    Disk,
+    Ring,
+    Clusters,
+    Lattice,
+    Galaxies
+}
+
+impl Generator {
+    pub const ALL: [Generator; 7] = [Generator::Plummer, Generator::King, Generator::Disk, Generator::Ring, Generator::Clusters, Generator::Lattice, Generator::Galaxies];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Generator::Plummer => "plummer",
+            Generator::King => "king",
+            Generator::Disk => "disk",
+            Generator::Ring => "ring",
+            Generator::Clusters => "clusters",
+            Generator::Lattice => "lattice",
+            Generator::Galaxies => "galaxies"
+        }
+    }
+}
+
+pub struct GeneratorSettings {
+    pub generator: Generator,
+    pub seed: u64,
+    pub count: usize,
+    pub mass: f32,
+    pub radius: f32,
+    pub inner_radius: f32,
+    pub central_mass: f32,
+    pub central_fixed: bool,
+    pub clusters: usize,
+    /// Kinetic over potential energy the velocities are scaled to, 0.5 is equilibrium.
+    pub virial_ratio: f32,
+    pub columns: usize,
+    pub rows: usize,
+    pub spacing: f32,
+    pub jitter: f32,
+    pub core_mass: f32,
+    pub mass_ratio: f32,
+    pub separation: f32,
+    pub impact_parameter: f32,
+    pub approach_speed: f32,
+    pub retrograde: [bool; 2],
+    pub keep_existing: bool
+}
+
+impl GeneratorSettings {
+    pub fn new() -> Self {
+        Self {
+            generator: Generator::Plummer,
+            seed: 0,
+            count: 100,
+            mass: 30.,
+            radius: 200.,
+            inner_radius: 40.,
+            central_mass: 10.,
+            central_fixed: false,
+            clusters: 3,
+            virial_ratio: 0.5,
+            columns: 10,
+            rows: 10,
+            spacing: 40.,
+            jitter: 0.,
+            core_mass: 100.,
+            mass_ratio: 1.,
+            separation: 800.,
+            impact_parameter: 400.,
+            approach_speed: 2.5,
+            retrograde: [false, false],
+            keep_existing: false
+        }
+    }
+
+    pub fn generate(&self, rng: &mut Random, integrator: Integrator, softening: f32) -> Vec<Mover> {
+        let count = self.count.max(1);
+        let m = self.mass / count as f32;
+
+        match self.generator {
+            Generator::Plummer => {
+                let mut bodies = (0..count).map(|_| plummer_body(rng, self.radius as f64, self.mass as f64, m)).collect::<Vec<_>>();
+                scale_to_virial(&mut bodies, self.virial_ratio, integrator, softening);
+                bodies
+            },
+            Generator::King => {
+                // Not a King (1966) model, its velocities would follow the distribution function
+                let mut bodies = (0..count).map(|_| {
+                    let pos = king_position(rng, self.inner_radius as f64, self.radius as f64);
+                    Mover::new(pos.x as f32, pos.y as f32, m, gaussian(rng) as f32, gaussian(rng) as f32)
+                }).collect::<Vec<_>>();
+                scale_to_virial(&mut bodies, self.virial_ratio, integrator, softening);
+                bodies
+            },
+            Generator::Disk => {
+                let positions = disk_positions(rng, count, self.inner_radius as f64, self.radius as f64);
+                let center = Mover::new(0., 0., self.central_mass, 0., 0.).apply_forces(!self.central_fixed);
+                rotating(center, positions, m, integrator, softening)
+            },
+            Generator::Ring => {
+                let phase = rng.gen::<f64>() * TAU;
+                let positions = (0..count).map(|k| {
+                    direction(phase + TAU * k as f64 / count as f64) * self.radius as f64
+                }).collect();
+                let center = Mover::new(0., 0., self.central_mass, 0., 0.).apply_forces(!self.central_fixed);
+                rotating(center, positions, m, integrator, softening)
+            },
+            Generator::Clusters => {
+                let clusters = self.clusters.clamp(1, count);
+                let centers = (0..clusters).map(|_| uniform_in_disk(rng, self.radius as f64)).collect::<Vec<_>>();
+                let mut bodies = (0..count).map(|k| {
+                    let pos = centers[k % clusters] + uniform_in_disk(rng, self.inner_radius as f64);
+                    Mover::new(pos.x as f32, pos.y as f32, m, gaussian(rng) as f32, gaussian(rng) as f32)
+                }).collect::<Vec<_>>();
+                scale_to_virial(&mut bodies, self.virial_ratio, integrator, softening);
+                bodies
+            },
+            Generator::Lattice => {
+                let (columns, rows) = (self.columns.max(1), self.rows.max(1));
+                let m = self.mass / (columns * rows) as f32;
+                let corner = Vec2::new((columns - 1) as f32, (rows - 1) as f32) * self.spacing / 2.;
+                (0..columns * rows).map(|k| {
+                    let node = Vec2::new((k % columns) as f32, (k / columns) as f32) * self.spacing - corner;
+                    let pos = node + uniform_in_disk(rng, self.jitter as f64).as_vec2();
+                    Mover::new(pos.x, pos.y, m, 0., 0.)
+                }).collect()
+            },
+            Generator::Galaxies => self.galaxies(rng, count, integrator, softening)
+        }
+    }
+
+    /// Two disk galaxies on a collision course (Toomre and Toomre 1972).
+    fn galaxies(&self, rng: &mut Random, count: usize, integrator: Integrator, softening: f32) -> Vec<Mover> {
+        let ratio = self.mass_ratio.max(0.01);
+        let scales = [1., ratio];
+
+        let second = ((count as f32 * ratio / (1. + ratio)).round() as usize).min(count);
+        let counts = [count - second, second];
+        let m = self.mass * (1. + ratio) / count as f32;
+
+        let offset = DVec2::new(self.separation as f64, self.impact_parameter as f64);
+        let velocity = DVec2::new(-self.approach_speed as f64, 0.);
+        let orbit = if offset.perp_dot(velocity) < 0. { -1. } else { 1. };
+
+        let galaxies = (0..2).map(|k| {
+            let size = scales[k].sqrt() as f64;
+            let positions = disk_positions(rng, counts[k], self.inner_radius as f64 * size, self.radius as f64 * size);
+            let core = Mover::new(0., 0., self.core_mass * scales[k], 0., 0.);
+            let mut bodies = rotating(core, positions, m, integrator, softening);
+
+            let spin = if self.retrograde[k] { -orbit } else { orbit };
+            for body in bodies.iter_mut() {
+                body.vel *= spin as f32;
+            }
+            bodies
+        }).collect::<Vec<_>>();
+
+        // Both galaxies move about their common centre of mass, which stays at the origin
+        let masses = galaxies.iter().map(|g| g.iter().map(|b| b.m as f64).sum::<f64>()).collect::<Vec<_>>();
+        let total = masses[0] + masses[1];
+        let shifts = [(-offset * masses[1] / total, -velocity * masses[1] / total), (offset * masses[0] / total, velocity * masses[0] / total)];
+
+        galaxies.into_iter().zip(shifts).flat_map(|(bodies, (pos, vel))| {
+            bodies.into_iter().map(move |mut body| {
+                body.pos += pos.as_vec2();
+                body.vel += vel.as_vec2();
+                body
+            })
+        }).collect()
+    }
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn rotating(center: Mover, positions: Vec<DVec2>, m: f32, integrator: Integrator, softening: f32) -> Vec<Mover> {
+    let mut bodies = vec![center];
+    bodies.extend(positions.iter().map(|p| Mover::new(p.x as f32, p.y as f32, m, 0., 0.)));
+
+    for i in 1..bodies.len() {
+        let radial = -bodies[i].pos.normalize_or_zero();
+        let inward = field::accelerations(&bodies, i, integrator, softening).iter().map(|(_, acc)| acc.dot(radial)).sum::<f32>();
+        let speed = (inward.max(0.) * bodies[i].pos.length()).sqrt();
+        bodies[i].vel = bodies[i].pos.normalize_or_zero().perp() * speed;
+    }
+    bodies
+}
+
+fn disk_positions(rng: &mut Random, count: usize, inner: f64, outer: f64) -> Vec<DVec2> {
+    let inner = inner.min(outer);
+    (0..count).map(|_| {
+        let r = (inner * inner + rng.gen::<f64>() * (outer * outer - inner * inner)).sqrt();
+        direction(rng.gen::<f64>() * TAU) * r
+    }).collect()
+}
+
+fn direction(angle: f64) -> DVec2 {
+    let (sin, cos) = vec_math::sin_cos(angle);
+    DVec2::new(cos, sin)
+}
+
+fn uniform_in_disk(rng: &mut Random, radius: f64) -> DVec2 {
+    direction(rng.gen::<f64>() * TAU) * radius * rng.gen::<f64>().sqrt()
+}
+
+fn gaussian(rng: &mut Random) -> f64 {
+    (0..12).map(|_| rng.gen::<f64>()).sum::<f64>() - 6.
+}
+
+/// Body of a Plummer sphere with scale radius `a` and total mass `total`, laid flat into the
+/// plane. The speed comes from the distribution function by rejection (Aarseth, Hénon and
+/// Wielen 1974).
+fn plummer_body(rng: &mut Random, a: f64, total: f64, m: f32) -> Mover {
+    let r = loop {
+        let u = rng.gen::<f64>();
+        if u > 0. {
+            let r = a / (1. / vec_math::cbrt(u * u) - 1.).sqrt();
+            if r <= PLUMMER_CUTOFF * a {
+                break r;
+            }
+        }
+    };
+
+    let q = loop {
+        let (x, y) = (rng.gen::<f64>(), rng.gen::<f64>());
+        let s = 1. - x * x;
+        if 0.1 * y < x * x * s * s * s * s.sqrt() {
+            break x;
+        }
+    };
+    let escape = (2. * G as f64 * total / a).sqrt() / (1. + r * r / (a * a)).sqrt().sqrt();
+
+    let pos = direction(rng.gen::<f64>() * TAU) * r;
+    let vel = direction(rng.gen::<f64>() * TAU) * q * escape;
+    Mover::new(pos.x as f32, pos.y as f32, m, vel.x as f32, vel.y as f32)
+}
+
+/// King's (1962) surface density profile, by rejection.
+fn king_position(rng: &mut Random, core: f64, tidal: f64) -> DVec2 {
+    let core = core.max(f64::EPSILON);
+    let profile = |r: f64| 1. / (1. + r * r / (core * core)).sqrt();
+    let edge = profile(tidal);
+    let density = |r: f64| (profile(r) - edge) * (profile(r) - edge);
+    let peak = density(0.);
+
+    loop {
+        let pos = uniform_in_disk(rng, tidal);
+        if rng.gen::<f64>() * peak < density(pos.length()) {
+            return pos;
+        }
+    }
+}
+
+/// Removes the drift of the centre of mass and scales the velocities so that the kinetic
+/// energy is `ratio` times the magnitude of the virial of Clausius, the sum of `r · F` over
+/// the pairs. For an inverse square law that is the potential energy, the Euler law has
+/// no potential that vanishes far away but its virial still tells equilibrium apart.
+fn scale_to_virial(bodies: &mut [Mover], ratio: f32, integrator: Integrator, softening: f32) {
+    let mass = bodies.iter().map(|b| b.m).sum::<f32>();
+    if mass <= 0. {
+        return;
+    }
+    let drift = bodies.iter().map(|b| b.vel * b.m).sum::<Vec2>() / mass;
+    for body in bodies.iter_mut() {
+        body.vel -= drift;
+    }
+
+    let kinetic = bodies.iter().map(|b| b.m as f64 * b.vel.as_dvec2().length_squared() / 2.).sum::<f64>();
+    let mut virial = 0.;
+    for i in 0..bodies.len() {
+        for j in i + 1..bodies.len() {
+            let d = bodies[j].pos - bodies[i].pos;
+            virial -= bodies[i].m as f64 * d.dot(field::pair_acceleration(integrator, bodies[j].m, d, softening)) as f64;
+        }
+    }
+
+    if kinetic > 0. && virial < 0. {
+        let scale = (ratio as f64 * -virial / kinetic).sqrt() as f32;
+        for body in bodies.iter_mut() {
+            body.vel *= scale;
+        }
+    }
+}
\ No newline at end of file
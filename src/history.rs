@@ -0,0 +1,127 @@
+use std::collections::VecDeque;
+
+use crate::{mover::Mover, diagnostics::Diagnostics};
+
+/// A quantity that can be recorded over simulated time. Bodies are referred to by their
+/// id, so a series stays on its body when others are removed.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Quantity {
+    EnergyDrift,
+    VirialRatio,
+    Distance(usize, usize),
+    Speed(usize),
+    /// Growth ln(d / d0) of the distance to the shadow trajectory.
+    Divergence
+}
+
+impl Quantity {
+    pub const ALL: [Quantity; 5] = [Quantity::EnergyDrift, Quantity::VirialRatio, Quantity::Distance(0, 1), Quantity::Speed(0), Quantity::Divergence];
+
+    /// Translation key of the quantity.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quantity::EnergyDrift => "drift",
+            Quantity::VirialRatio => "virial",
+            Quantity::Distance(..) => "distance",
+            Quantity::Speed(..) => "speed",
+            Quantity::Divergence => "divergence"
+        }
+    }
+
+    pub fn same_kind(&self, other: &Quantity) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    fn needs_diagnostics(&self) -> bool {
+        matches!(self, Quantity::EnergyDrift | Quantity::VirialRatio)
+    }
+
+    /// `None` when the quantity can't be measured, for example when a body no longer exists.
+    fn measure(&self, planets: &[Mover], diagnostics: Option<&(Diagnostics, f64)>, divergence: Option<f64>) -> Option<f64> {
+        match *self {
+            Quantity::EnergyDrift => diagnostics.map(|(_, drift)| *drift),
+            Quantity::VirialRatio => diagnostics.map(|(d, _)| 2. * d.kinetic / d.potential.abs()),
+            Quantity::Distance(i, j) => Some(find(planets, i)?.pos.distance(find(planets, j)?.pos) as f64),
+            Quantity::Speed(i) => Some(find(planets, i)?.vel.length() as f64),
+            Quantity::Divergence => divergence
+        }
+    }
+}
+
+fn find(planets: &[Mover], id: usize) -> Option<&Mover> {
+    planets.iter().find(|p| p.id == id)
+}
+
+pub struct Series {
+    pub quantity: Quantity,
+    /// (time, value) samples, oldest first.
+    pub samples: VecDeque<[f64; 2]>
+}
+
+/// Ring-buffered samples of the chosen quantities.
+pub struct History {
+    pub series: Vec<Series>,
+    /// Most samples kept per series, older ones are dropped.
+    pub capacity: usize,
+    /// Ticks between two samples.
+    pub interval: u32,
+    ticks: u32
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            series: Vec::new(),
+            capacity: 4096,
+            interval: 1,
+            ticks: 0
+        }
+    }
+
+    pub fn add(&mut self, quantity: Quantity) {
+        if !self.series.iter().any(|s| s.quantity == quantity) {
+            self.series.push(Series { quantity, samples: VecDeque::new() });
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.ticks = 0;
+        for series in self.series.iter_mut() {
+            series.samples.clear();
+        }
+    }
+
+    pub fn needs_diagnostics(&self) -> bool {
+        self.series.iter().any(|s| s.quantity.needs_diagnostics())
+    }
+
+    /// Counts a tick, returns true when a sample is due.
+    pub fn tick(&mut self) -> bool {
+        self.ticks += 1;
+        if self.ticks >= self.interval {
+            self.ticks = 0;
+            return !self.series.is_empty();
+        }
+        false
+    }
+
+    /// Samples every series. `diagnostics` holds the measured diagnostics and energy drift,
+    /// it's only needed when `needs_diagnostics` is true. `divergence` comes from the shadow
+    /// trajectory, when there is one.
+    pub fn record(&mut self, time: f64, planets: &[Mover], diagnostics: Option<(Diagnostics, f64)>, divergence: Option<f64>) {
+        for series in self.series.iter_mut() {
+            if let Some(value) = series.quantity.measure(planets, diagnostics.as_ref(), divergence) {
+                if series.samples.len() >= self.capacity {
+                    series.samples.pop_front();
+                }
+                series.samples.push_back([time, value]);
+            }
+        }
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
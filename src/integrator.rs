@@ -0,0 +1,307 @@
+use notan::math::DVec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{mover::Mover, regularization::{self, Pair}, G};
+
+/// Deepest block level, the smallest timestep is `1 / 2^MAX_LEVEL` of a tick.
+pub const MAX_LEVEL: u32 = 20;
+
+/// Accuracy parameter of the Aarseth timestep criterion.
+const ETA: f64 = 0.02;
+/// Accuracy parameter of the starting timestep criterion.
+const ETA_START: f64 = 0.01;
+
+/// Fixed leapfrog steps per tick.
+pub const LEAPFROG_STEPS: u32 = 16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Integrator {
+    Euler,
+    Hermite,
+    Leapfrog
+}
+
+impl Integrator {
+    pub const ALL: [Integrator; 3] = [Integrator::Euler, Integrator::Hermite, Integrator::Leapfrog];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Integrator::Euler => "euler",
+            Integrator::Hermite => "hermite",
+            Integrator::Leapfrog => "leapfrog"
+        }
+    }
+
+    /// Smallest softening the force law works with. The Euler law divides by the clamped
+    /// distance, so it needs one to keep coincident bodies apart.
+    pub fn min_softening(&self) -> f32 {
+        match self {
+            Integrator::Euler => 0.1,
+            Integrator::Hermite | Integrator::Leapfrog => 0.
+        }
+    }
+
+    /// Advances the bodies by one tick.
+    pub fn step(&self, planets: &mut [Mover], softening: f32, regularization: Option<f32>) {
+        match self {
+            Integrator::Euler => euler_step(planets, softening),
+            Integrator::Hermite => hermite_step(planets, softening, regularization),
+            Integrator::Leapfrog => leapfrog_step(planets, softening)
+        }
+    }
+
+    /// Rough number of pair interactions one tick of `planets` takes. Hermite bodies are
+    /// evaluated once per step of their current block level.
+    pub fn cost(&self, planets: &[Mover]) -> u64 {
+        let n = planets.len() as u64;
+        match self {
+            Integrator::Euler => n * n,
+            Integrator::Hermite => n * planets.iter().map(|p| 1u64 << p.level.min(MAX_LEVEL)).sum::<u64>(),
+            Integrator::Leapfrog => LEAPFROG_STEPS as u64 * n * n
+        }
+    }
+}
+
+/// The original integrator: every body takes one step per tick with the clamped force law.
+pub fn euler_step(planets: &mut [Mover], softening: f32) {
+    for i in 0..planets.len() {
+        let temp_mover = planets[i];
+        for (j, planet) in planets.iter_mut().enumerate() {
+            if i != j {
+                temp_mover.attract(planet, softening);
+            }
+        }
+    }
+
+    for planet in planets.iter_mut() {
+        planet.update();
+    }
+}
+
+/// Advances the system by one tick with fixed kick-drift-kick leapfrog steps. The scheme is
+/// time symmetric, so flipping every velocity makes the bodies retrace their paths. The state
+/// is carried over in double precision, rounding it every tick would break the symmetry.
+pub fn leapfrog_step(planets: &mut [Mover], softening: f32) {
+    let dt = 1. / LEAPFROG_STEPS as f64;
+    let eps_sq = softening as f64 * softening as f64;
+
+    let moving = planets.iter().map(|p| p.apply_forces).collect::<Vec<_>>();
+    let mass = planets.iter().map(|p| p.m as f64).collect::<Vec<_>>();
+    let (mut pos, mut vel): (Vec<_>, Vec<_>) = planets.iter().map(|p| p.exact_state()).unzip();
+    let mut acc = (0..pos.len()).map(|i| accel(i, &pos, &mass, eps_sq)).collect::<Vec<_>>();
+
+    for _ in 0..LEAPFROG_STEPS {
+        for i in (0..pos.len()).filter(|&i| moving[i]) {
+            vel[i] += acc[i] * (dt / 2.);
+            pos[i] += vel[i] * dt;
+        }
+
+        acc = (0..pos.len()).map(|i| accel(i, &pos, &mass, eps_sq)).collect();
+
+        for i in (0..pos.len()).filter(|&i| moving[i]) {
+            vel[i] += acc[i] * (dt / 2.);
+        }
+    }
+
+    for (i, planet) in planets.iter_mut().enumerate().filter(|(_, p)| p.apply_forces) {
+        planet.pos = pos[i].as_vec2();
+        planet.vel = vel[i].as_vec2();
+        planet.acc = acc[i].as_vec2();
+        planet.exact = (pos[i], vel[i]);
+    }
+}
+
+/// Advances the system by one tick with a fourth-order Hermite predictor-corrector.
+/// Every body gets its own power-of-two fraction of a tick as its timestep, so bodies
+/// in tight orbits are stepped many times while the rest only move once or twice.
+/// The substeps run in double precision, the bodies are only rounded back once per tick.
+///
+/// With `regularization` set, close binaries within that radius move through the tick as
+/// a single body at their centre of mass, while their relative orbit is integrated
+/// without softening in regularized coordinates.
+pub fn hermite_step(planets: &mut [Mover], softening: f32, regularization: Option<f32>) {
+    let n = planets.len();
+    let full: u32 = 1 << MAX_LEVEL;
+    let eps_sq = softening as f64 * softening as f64;
+
+    let mut moving = planets.iter().map(|p| p.apply_forces).collect::<Vec<_>>();
+    let mass = planets.iter().map(|p| p.m as f64).collect::<Vec<_>>();
+    let mut pos = planets.iter().map(|p| p.pos.as_dvec2()).collect::<Vec<_>>();
+    let mut vel = planets.iter().map(|p| if p.apply_forces { p.vel.as_dvec2() } else { DVec2::ZERO }).collect::<Vec<_>>();
+    let mut level = planets.iter().map(|p| p.level).collect::<Vec<_>>();
+
+    let mut pairs = match regularization {
+        Some(radius) => regularization::find_pairs(&pos, &vel, &mass, &moving, radius as f64, eps_sq),
+        None => Vec::new()
+    };
+    for pair in &pairs {
+        let (i, j) = (pair.i, pair.j);
+        pos[i] = (pos[i] * mass[i] + pos[j] * mass[j]) / pair.mass();
+        vel[i] = (vel[i] * mass[i] + vel[j] * mass[j]) / pair.mass();
+        moving[j] = false;
+    }
+
+    // Every tick starts synchronised, so the forces are refreshed here. This also picks
+    // up bodies that were added or removed between ticks.
+    let (src_pos, src_vel) = resolve(&pairs, &pos, &vel);
+    let (mut acc, mut jerk): (Vec<_>, Vec<_>) = (0..n).map(|i| body_accel_jerk(i, &pairs, &src_pos, &src_vel, &mass, eps_sq)).unzip();
+    for ((level, a), j) in level.iter_mut().zip(&acc).zip(&jerk) {
+        *level = (*level).max(level_for(ETA_START * a.length() / j.length()));
+    }
+
+    let mut time = vec![0u32; n];
+    let mut pred_pos = pos.clone();
+    let mut pred_vel = vel.clone();
+
+    loop {
+        let next = (0..n)
+            .filter(|&i| moving[i])
+            .map(|i| time[i] + (full >> level[i]))
+            .min();
+
+        let next = match next {
+            Some(next) if next <= full => next,
+            _ => break
+        };
+
+        for i in (0..n).filter(|&i| moving[i]) {
+            let dt = block_to_dt(next - time[i]);
+            pred_pos[i] = pos[i] + vel[i] * dt + acc[i] * (dt * dt / 2.) + jerk[i] * (dt * dt * dt / 6.);
+            pred_vel[i] = vel[i] + acc[i] * dt + jerk[i] * (dt * dt / 2.);
+        }
+
+        let active = (0..n)
+            .filter(|&i| moving[i] && time[i] + (full >> level[i]) == next)
+            .collect::<Vec<_>>();
+
+        let (src_pos, src_vel) = resolve(&pairs, &pred_pos, &pred_vel);
+        let corrected = active.iter().map(|&i| body_accel_jerk(i, &pairs, &src_pos, &src_vel, &mass, eps_sq)).collect::<Vec<_>>();
+
+        for (&i, (acc1, jerk1)) in active.iter().zip(corrected) {
+            let dt = block_to_dt(next - time[i]);
+            let (pos0, vel0) = (pos[i], vel[i]);
+
+            let snap = ((acc[i] - acc1) * -6. - (jerk[i] * 4. + jerk1 * 2.) * dt) / (dt * dt);
+            let crackle = ((acc[i] - acc1) * 12. + (jerk[i] + jerk1) * (6. * dt)) / (dt * dt * dt);
+
+            pos[i] = pred_pos[i] + snap * (dt * dt * dt * dt / 24.) + crackle * (dt * dt * dt * dt * dt / 120.);
+            vel[i] = pred_vel[i] + snap * (dt * dt * dt / 6.) + crackle * (dt * dt * dt * dt / 24.);
+            acc[i] = acc1;
+            jerk[i] = jerk1;
+            pred_pos[i] = pos[i];
+            pred_vel[i] = vel[i];
+            time[i] = next;
+
+            if let Some(pair) = pairs.iter_mut().find(|pair| pair.i == i) {
+                pair.advance(dt, pos0, vel0, &src_pos, &mass, eps_sq);
+            }
+
+            let snap1 = snap + crackle * dt;
+            let (a, j, s, c) = (acc1.length(), jerk1.length(), snap1.length(), crackle.length());
+            let new_level = level_for((ETA * (a * s + j * j) / (j * c + s * s)).sqrt());
+
+            // Timesteps may shrink freely but only grow by a factor of two, and only when
+            // the body lands on a boundary of the coarser block.
+            if new_level > level[i] {
+                level[i] = new_level;
+            } else if new_level < level[i] && time[i].is_multiple_of(full >> (level[i] - 1)) {
+                level[i] -= 1;
+            }
+        }
+    }
+
+    let (pos, vel) = resolve(&pairs, &pos, &vel);
+    for pair in &pairs {
+        (acc[pair.j], jerk[pair.j], level[pair.j]) = (acc[pair.i], jerk[pair.i], level[pair.i]);
+    }
+
+    for (i, planet) in planets.iter_mut().enumerate().filter(|(_, p)| p.apply_forces) {
+        planet.pos = pos[i].as_vec2();
+        planet.vel = vel[i].as_vec2();
+        planet.acc = acc[i].as_vec2();
+        planet.jerk = jerk[i].as_vec2();
+        planet.level = level[i];
+    }
+}
+
+/// Acceleration and jerk on body `i`. A regularized pair is stored at its centre of mass
+/// in `i`, it gets the mass weighted mean of what its two bodies feel.
+fn body_accel_jerk(i: usize, pairs: &[Pair], pos: &[DVec2], vel: &[DVec2], mass: &[f64], eps_sq: f64) -> (DVec2, DVec2) {
+    match pairs.iter().find(|pair| pair.i == i) {
+        Some(pair) => {
+            let skip = (pair.i, pair.j);
+            let (acc_i, jerk_i) = accel_jerk(pos[pair.i], vel[pair.i], skip, pos, vel, mass, eps_sq);
+            let (acc_j, jerk_j) = accel_jerk(pos[pair.j], vel[pair.j], skip, pos, vel, mass, eps_sq);
+            let (w_i, w_j) = (mass[pair.i] / pair.mass(), mass[pair.j] / pair.mass());
+            (acc_i * w_i + acc_j * w_j, jerk_i * w_i + jerk_j * w_j)
+        }
+        None => accel_jerk(pos[i], vel[i], (i, i), pos, vel, mass, eps_sq)
+    }
+}
+
+/// Acceleration and jerk at `x` moving with `v` from every body except the ones in `skip`,
+/// using Plummer softening. Massless bodies are skipped as sources.
+fn accel_jerk(x: DVec2, v: DVec2, skip: (usize, usize), pos: &[DVec2], vel: &[DVec2], mass: &[f64], eps_sq: f64) -> (DVec2, DVec2) {
+    let mut acc = DVec2::ZERO;
+    let mut jerk = DVec2::ZERO;
+
+    for j in 0..mass.len() {
+        if j != skip.0 && j != skip.1 && mass[j] > 0. {
+            let dr = pos[j] - x;
+            let dv = vel[j] - v;
+            let r_sq = dr.length_squared() + eps_sq;
+            let inv_r3 = 1. / (r_sq * r_sq.sqrt());
+            let rv = 3. * dr.dot(dv) / r_sq;
+
+            acc += dr * (G as f64 * mass[j] * inv_r3);
+            jerk += (dv - dr * rv) * (G as f64 * mass[j] * inv_r3);
+        }
+    }
+
+    (acc, jerk)
+}
+
+/// Softened acceleration on body `i` from every other body.
+fn accel(i: usize, pos: &[DVec2], mass: &[f64], eps_sq: f64) -> DVec2 {
+    let mut acc = DVec2::ZERO;
+
+    for j in 0..mass.len() {
+        if i != j && mass[j] > 0. {
+            let dr = pos[j] - pos[i];
+            let r_sq = dr.length_squared() + eps_sq;
+            acc += dr * (G as f64 * mass[j] / (r_sq * r_sq.sqrt()));
+        }
+    }
+
+    acc
+}
+
+/// Copies the state with every regularized pair split back into its two bodies, which is
+/// what the rest of the system feels.
+fn resolve(pairs: &[Pair], pos: &[DVec2], vel: &[DVec2]) -> (Vec<DVec2>, Vec<DVec2>) {
+    let (mut pos, mut vel) = (pos.to_vec(), vel.to_vec());
+    for pair in pairs {
+        ((pos[pair.i], vel[pair.i]), (pos[pair.j], vel[pair.j])) = pair.split(pos[pair.i], vel[pair.i]);
+    }
+    (pos, vel)
+}
+
+/// The coarsest block level whose timestep is not longer than `dt`.
+fn level_for(dt: f64) -> u32 {
+    if !dt.is_finite() {
+        return 0;
+    }
+
+    let mut level = 0;
+    let mut step = 1.;
+    while step > dt && level < MAX_LEVEL {
+        step /= 2.;
+        level += 1;
+    }
+    level
+}
+
+fn block_to_dt(blocks: u32) -> f64 {
+    blocks as f64 / (1u32 << MAX_LEVEL) as f64
+}
\ No newline at end of file
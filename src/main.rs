@@ -4,13 +4,42 @@ pub mod pattern_loader;
 pub mod camera;
 pub mod translations;
 pub mod input_manager;
+pub mod integrator;
+pub mod simulation;
+pub mod regularization;
+pub mod diagnostics;
+pub mod history;
+pub mod orbit;
+pub mod events;
+pub mod chaos;
+pub mod section;
+pub mod field;
+pub mod prediction;
+pub mod scenario;
+pub mod files;
+pub mod generators;
+pub mod units;
+pub mod solar_system;
 
-use std::{ops::AddAssign, f32::consts::PI};
+use std::{ops::AddAssign, f32::consts::PI, collections::HashMap};
 
 use camera::Camera2D;
+use diagnostics::DiagnosticsLog;
+use field::{PotentialGrid, WarpedGrid};
+use events::{Event, EventKind};
+use history::Quantity;
+use section::{Axis, Direction};
+use integrator::{Integrator, MAX_LEVEL};
 use mover::Mover;
-use notan::{notan_main, AppState, prelude::{Graphics, App, WindowConfig, Color, RenderTexture, TextureFilter, Plugins}, draw::{DrawConfig, CreateDraw, DrawImages, DrawShapes}, egui::{EguiConfig, EguiPluginSugar, SidePanel, panel::Side, Slider, ComboBox, Layout, Align, Window, DragValue, ScrollArea, RichText}, math::Vec2};
-use pattern_loader::PatternLoader;
+use orbit::Elements;
+use units::Units;
+use notan::{notan_main, AppState, prelude::{Graphics, App, WindowConfig, Color, RenderTexture, Texture, TextureFilter, Plugins}, draw::{Draw, DrawConfig, CreateDraw, DrawImages, DrawShapes}, egui::{self, EguiConfig, EguiPluginSugar, SidePanel, panel::Side, Slider, ComboBox, Layout, Align, Window, DragValue, ScrollArea, RichText, Button, plot::{Plot, Line, PlotPoints, Legend, Points}}, math::Vec2, random::rand::random};
+use pattern_loader::{PatternLoader, Pattern, SCENARIO_DIR};
+use scenario::Scenario;
+use files::Uploads;
+use generators::{Generator, GeneratorSettings};
+use prediction::Forecast;
+use simulation::{Simulation, EscapePolicy};
 use translations::Translations;
 
 pub const TRAIL_TEX_WIDTH: i32 = (1920. * 8.) as i32;
@@ -18,100 +47,241 @@ pub const TRAIL_TEX_HEIGHT: i32 = (1080. * 8.) as i32;
 
 pub const G: f32 = 10.0;
 
+const DIAGNOSTICS_LOG: &str = "diagnostics.csv";
+const EVENTS_CSV: &str = "events.csv";
+const SECTION_CSV: &str = "section.csv";
+
+/// Samples of the potential heatmap across the viewport.
+const POTENTIAL_GRID: (usize, usize) = (160, 90);
+const CONTOUR_LEVELS: usize = 12;
+/// Rough on-screen size of a spacetime grid cell in pixels.
+const SPACETIME_CELL: f32 = 40.;
+
 #[derive(AppState)]
 pub struct State {
-    planets: Vec<Mover>,
+    sim: Simulation,
     trail_texture: RenderTexture,
+    potential_texture: Texture,
     editor_info: EditorInfo,
     pattern_loader: PatternLoader,
     camera: Camera2D,
     camera_zoom: f32,
     trans: Translations,
     new_body: NewBodyInfo,
-    object_tracking: Option<usize>
+    preview: Option<Forecast>,
+    forecast: Option<Forecast>,
+    object_tracking: Option<usize>,
+    /// Keeps the camera on the barycenter, the trails stay in world space so they are not disturbed.
+    track_barycenter: bool,
+    /// Bodies whose orbital elements are measured around a chosen parent instead of their
+    /// dominant attractor.
+    orbit_parents: HashMap<usize, usize>,
+    diagnostics_log: Option<DiagnosticsLog>,
+    log_error: Option<String>,
+    /// Outcome of the last export.
+    export_status: Option<String>,
+    new_series: Quantity,
+    /// File the scenario is saved to and loaded from, in `SCENARIO_DIR`.
+    scenario_file: String,
+    scenario_status: Option<String>,
+    uploads: Uploads,
+    generator: GeneratorSettings
 }
 
 struct NewBodyInfo {
     mass: f32,
     moveable: bool,
     dir: f32,
-    force: f32
+    force: f32,
+    /// Place the body on an orbit around `parent` instead of at the mouse.
+    from_orbit: bool,
+    parent: usize,
+    a: f32,
+    e: f32,
+    /// Argument of periapsis and true anomaly in degrees.
+    arg_periapsis: f32,
+    true_anomaly: f32,
+    /// Ticks of the predicted path drawn before the body is added.
+    preview_ticks: u32
 }
 
+/// Longest real frame time in seconds that is turned into simulated time. The rest of a
+/// slow frame is dropped, so it can't snowball into slower ones.
+const MAX_FRAME_TIME: f32 = 0.1;
+/// Pair interactions of the forecast integrated per frame.
+const FORECAST_BUDGET: u64 = 2_000_000;
+/// Pair interactions of the placement preview integrated per frame.
+const PREVIEW_BUDGET: u64 = 2_000_000;
+/// A new forecast starts once the simulation is this fraction of the forecast time past
+/// the shown one.
+const FORECAST_REFRESH: u32 = 10;
+
 struct EditorInfo {
-    sim_speed: i32,
+    /// Simulated time units per real second.
+    sim_speed: f32,
+    /// Simulated time that is due but not yet stepped, in ticks.
+    accumulator: f32,
     show_trail: bool,
     show_bodies: bool,
+    show_plots: bool,
+    show_barycenter: bool,
+    show_events: bool,
+    show_section: bool,
+    show_generators: bool,
+    show_potential: bool,
+    show_contours: bool,
+    show_velocity: bool,
+    show_acceleration: bool,
+    /// Shows what every other body adds to the acceleration of the hovered or tracked body.
+    show_contributions: bool,
+    /// Arrow length per unit of velocity and of acceleration.
+    velocity_scale: f32,
+    acceleration_scale: f32,
+    show_spacetime: bool,
+    spacetime_strength: f32,
+    /// Predicted path of the hovered or tracked body, this many time units ahead.
+    show_forecast: bool,
+    forecast_time: u32,
+    /// Loaded patterns are moved into the barycentric frame.
+    barycentric_load: bool,
     paused: bool,
     editor_enabled: bool
 }
 
 impl State {
     fn new(app: &mut App, gfx: &mut Graphics) -> Self {
-        let mut planets = Vec::<Mover>::new();
+        let mut sim = Simulation::new();
 
         let pattern_loader = PatternLoader::new();
-        pattern_loader.load_pattern(&mut planets, 1);
+        pattern_loader.apply_settings(&mut sim);
+        pattern_loader.reload_pattern(&mut sim);
+        sim.reset();
+
+        let mut camera = Camera2D::new(0.0, 0.0, app.window().width() as f32, app.window().height() as f32);
+        let mut camera_zoom = 1.;
+        pattern_loader.reset_camera(&mut camera, &mut camera_zoom);
         
         Self {
-            planets,
+            sim,
             trail_texture: gfx.create_render_texture(TRAIL_TEX_WIDTH, TRAIL_TEX_HEIGHT).with_filter(TextureFilter::Linear, TextureFilter::Linear).build().unwrap(),
+            potential_texture: gfx.create_texture().from_bytes(&vec![0; POTENTIAL_GRID.0 * POTENTIAL_GRID.1 * 4], POTENTIAL_GRID.0 as i32, POTENTIAL_GRID.1 as i32).with_filter(TextureFilter::Linear, TextureFilter::Linear).build().unwrap(),
             editor_info: EditorInfo {
-                sim_speed: 1,
+                sim_speed: pattern_loader.chosen().sim_speed.unwrap_or(60.),
+                accumulator: 0.,
                 show_trail: true,
                 show_bodies: true,
+                show_plots: false,
+                show_barycenter: false,
+                show_events: false,
+                show_section: false,
+                show_generators: false,
+                show_potential: false,
+                show_contours: false,
+                show_velocity: false,
+                show_acceleration: false,
+                show_contributions: false,
+                velocity_scale: 20.,
+                acceleration_scale: 2000.,
+                show_spacetime: false,
+                spacetime_strength: 2000.,
+                show_forecast: false,
+                forecast_time: 2000,
+                barycentric_load: false,
                 paused: false,
                 editor_enabled: false
             },
             pattern_loader,
-            camera: Camera2D::new(0.0, 0.0, app.window().width() as f32, app.window().height() as f32),
-            camera_zoom: 1.,
+            camera,
+            camera_zoom,
             trans: Translations::new(),
+            preview: None,
+            forecast: None,
             new_body: NewBodyInfo {
                 mass: 3.,
                 moveable: true,
                 dir: 0.,
-                force: 1.
+                force: 1.,
+                from_orbit: false,
+                parent: 0,
+                a: 100.,
+                e: 0.,
+                arg_periapsis: 0.,
+                true_anomaly: 0.,
+                preview_ticks: 500
             },
-            object_tracking: None
+            object_tracking: None,
+            track_barycenter: false,
+            orbit_parents: HashMap::new(),
+            diagnostics_log: None,
+            log_error: None,
+            export_status: None,
+            new_series: Quantity::EnergyDrift,
+            scenario_file: "playground.json".to_string(),
+            scenario_status: None,
+            uploads: Uploads::default(),
+            generator: GeneratorSettings::new()
         }
     }
 }
 
 fn update(app: &mut App, state: &mut State) {
     if !state.editor_info.paused {
-        for planet in state.planets.iter_mut() {
-            planet.save_delta_pos();
+        for planet in state.sim.planets.iter_mut() {
+            planet.save_delta_pos(state.editor_info.accumulator);
         }
+
+        state.editor_info.accumulator += app.timer.delta_f32().min(MAX_FRAME_TIME) * state.editor_info.sim_speed;
+        let ticks = state.editor_info.accumulator as u32;
+        state.editor_info.accumulator = state.editor_info.accumulator.fract();
     
-        for _ in 0..state.editor_info.sim_speed {
-            for i in 0..state.planets.len() {
-                let temp_mover = state.planets.get_mut(i).unwrap().clone();
-                for j in 0..state.planets.len() {
-                    if i != j {
-                        temp_mover.attract(state.planets.get_mut(j).unwrap());
-                    }
-                }
-            }
-    
-            for i in 0..state.planets.len() {
-                state.planets.get_mut(i).unwrap().update(app);
+        let removed = state.sim.removed;
+        for _ in 0..ticks {
+            if !state.sim.step() {
+                state.editor_info.paused = true;
+                state.editor_info.accumulator = 0.;
+                break;
             }
+            log_diagnostics(state);
+        }
+
+        // Escapers were removed, indices into the bodies are no longer valid
+        if state.sim.removed != removed {
+            state.object_tracking = None;
+            state.orbit_parents.clear();
         }
     }
 
-    match state.object_tracking {
-        Some(i) => state.camera.set_position(state.planets.get(i).unwrap().pos.x, state.planets.get(i).unwrap().pos.y),
-        _ => ()
+    if let Some(i) = state.object_tracking {
+        let pos = state.sim.planets.get(i).unwrap().render_pos(state.editor_info.accumulator);
+        state.camera.set_position(pos.x, pos.y);
+    } else if let Some(pos) = state.sim.barycenter(state.editor_info.accumulator).filter(|_| state.track_barycenter) {
+        state.camera.set_position(pos.x, pos.y);
     }
 
     if state.editor_info.editor_enabled {
-        if app.mouse.right_was_pressed() {
-            let force = Vec2::from_angle(state.new_body.dir * PI / 180.) * state.new_body.force;
-            let mouse_pos = get_mouse_in_world(&(app.mouse.x, app.mouse.y), (app.window().width(), app.window().height()), &state.camera);
-            state.planets.push(Mover::new(mouse_pos.0, mouse_pos.1, state.new_body.mass, force.x, force.y).apply_forces(state.new_body.moveable));
+        if app.mouse.right_was_pressed() && !state.new_body.from_orbit {
+            let body = mouse_body(app, state);
+            let trail_color = state.sim.random_trail_color();
+            state.sim.planets.push(body.set_trail_color(trail_color));
+            state.sim.reset();
+        }
+    }
+
+    update_forecast(state);
+
+    for (name, text) in state.uploads.take() {
+        match Scenario::parse(&text) {
+            Ok(scenario) => {
+                state.pattern_loader.pattern = state.pattern_loader.add(Pattern::from_scenario(&scenario, &name));
+                state.pattern_loader.reload = true;
+                state.object_tracking = None;
+                state.scenario_status = Some(format!("{} {}", state.trans.get("loaded"), name));
+            },
+            Err(error) => state.scenario_status = Some(format!("{}: {}", name, error))
         }
     }
+
+    update_preview(app, state);
     
     input_manager::camera_control(app, &mut state.camera, &mut state.camera_zoom);
     input_manager::manage_shortcuts(app, state);
@@ -119,8 +289,8 @@ fn update(app: &mut App, state: &mut State) {
 
 fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut State) {
     let mut trail_draw = state.trail_texture.create_draw();
-    for planet in &state.planets {
-         planet.render_trail(&mut trail_draw);
+    for planet in &state.sim.planets {
+         planet.render_trail(&mut trail_draw, state.editor_info.accumulator);
     }
     gfx.render_to(&state.trail_texture, &trail_draw);
 
@@ -132,12 +302,53 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
         draw.image(&state.trail_texture.texture()).position(-TRAIL_TEX_WIDTH as f32 / 2., -TRAIL_TEX_HEIGHT as f32 / 2.);
     }
 
+    if state.editor_info.show_potential || state.editor_info.show_contours {
+        let size = state.camera.work_size / state.camera.scale();
+        let origin = state.camera.pos - size / 2.;
+        let grid = PotentialGrid::sample(&state.sim.planets, state.sim.integrator, state.sim.softening, origin, size, POTENTIAL_GRID.0, POTENTIAL_GRID.1);
+
+        if state.editor_info.show_potential {
+            gfx.update_texture(&mut state.potential_texture).with_data(&grid.pixels()).update().unwrap();
+            draw.image(&state.potential_texture).position(origin.x, origin.y).size(size.x, size.y);
+        }
+        if state.editor_info.show_contours {
+            for (a, b) in grid.contours(CONTOUR_LEVELS) {
+                draw.line((a.x, a.y), (b.x, b.y)).color(Color::from_rgba(1., 1., 1., 0.35)).width(1. / state.camera_zoom);
+            }
+        }
+    }
+
     if state.editor_info.show_bodies {
-        for planet in &state.planets {
-            planet.render(&mut draw);
+        for planet in &state.sim.planets {
+            planet.render(&mut draw, state.editor_info.accumulator);
+        }
+    }
+
+    if state.editor_info.show_spacetime {
+        draw_spacetime(&mut draw, state);
+    }
+
+    draw_vectors(&mut draw, state);
+
+    if state.editor_info.show_barycenter {
+        if let Some(center) = state.sim.barycenter(state.editor_info.accumulator) {
+            let size = 8. / state.camera_zoom;
+            draw.line((center.x - size, center.y), (center.x + size, center.y)).color(Color::YELLOW).width(2. / state.camera_zoom);
+            draw.line((center.x, center.y - size), (center.x, center.y + size)).color(Color::YELLOW).width(2. / state.camera_zoom);
+        }
+    }
+
+    if let Some(forecast) = &state.forecast {
+        if let Some(planet) = state.sim.planets.iter().find(|p| Some(p.id) == forecast.id) {
+            let color = Color::from_rgba(planet.trail_color.r, planet.trail_color.g, planet.trail_color.b, 0.6);
+            draw_dashed(&mut draw, forecast.path(state.sim.time), color, state.camera_zoom);
         }
     }
 
+    if let Some(preview) = &state.preview {
+        draw_dashed(&mut draw, preview.latest().0, Color::from_rgba(1., 1., 1., 0.4), state.camera_zoom);
+    }
+
     if state.editor_info.editor_enabled {
         let mouse_pos = get_mouse_in_world(&(app.mouse.x, app.mouse.y), (app.window().width(), app.window().height()), &state.camera);
 
@@ -154,7 +365,16 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
 
     if state.pattern_loader.handle_pattern_changes(&mut state.camera, &mut state.camera_zoom, &mut state.editor_info.editor_enabled) {
         clear_trail_texture(&mut state.trail_texture, gfx);
-        state.pattern_loader.reload_pattern(&mut state.planets);
+        state.pattern_loader.apply_settings(&mut state.sim);
+        state.pattern_loader.reload_pattern(&mut state.sim);
+        if let Some(speed) = state.pattern_loader.chosen().sim_speed {
+            state.editor_info.sim_speed = speed;
+        }
+        state.orbit_parents.clear();
+        if state.editor_info.barycentric_load {
+            state.sim.to_barycentric();
+        }
+        state.sim.reset();
     }
 
     let output = plugins.egui(|ctx| {
@@ -171,9 +391,23 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
             });
             ui.add_space(20.);
 
+            let lang = state.trans.chosen_lang.clone();
+            let tags = state.pattern_loader.tags();
+            if !tags.is_empty() {
+                let tag_txt = state.pattern_loader.tag.clone().unwrap_or_else(|| state.trans.get("alltopics"));
+                let mut tag = state.pattern_loader.tag.clone();
+                ComboBox::from_label(state.trans.get("topic")).selected_text(tag_txt).show_ui(ui, |ui| {
+                    ui.selectable_value(&mut tag, None, state.trans.get("alltopics"));
+                    for t in tags {
+                        ui.selectable_value(&mut tag, Some(t.to_string()), t);
+                    }
+                });
+                state.pattern_loader.tag = tag;
+            }
+
             ui.label(state.trans.get("choose"));
             let txt = if state.pattern_loader.chosen_pattern != 0 {
-                format!("{}{}", state.trans.get("pattern"), state.pattern_loader.chosen_pattern)
+                state.pattern_loader.chosen().name.get(&lang).to_string()
             } else {
                 state.trans.get("create")
             };
@@ -182,41 +416,243 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
                     state.object_tracking = None;
                 }
                 for i in 1..state.pattern_loader.patterns.len() {
-                    if ui.selectable_value(&mut state.pattern_loader.pattern, i, format!("{}{}", state.trans.get("pattern"), i)).clicked(){
+                    let pattern = &state.pattern_loader.patterns[i];
+                    if state.pattern_loader.tag.as_ref().is_some_and(|t| !pattern.tags.contains(t)) {
+                        continue;
+                    }
+                    let name = pattern.name.get(&lang).to_string();
+                    if ui.selectable_value(&mut state.pattern_loader.pattern, i, name).clicked(){
                         state.object_tracking = None;
                     }
                 }
             });
+            let chosen = state.pattern_loader.chosen();
+            if let Some(description) = &chosen.description {
+                ui.label(description.get(&lang));
+            }
+            if let Some(author) = &chosen.author {
+                ui.label(RichText::new(format!("{}: {}", state.trans.get("author"), author)).small());
+            }
+            if !chosen.tags.is_empty() {
+                ui.label(RichText::new(chosen.tags.join(", ")).small().weak());
+            }
+
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut state.scenario_file).desired_width(120.));
+                if ui.button(state.trans.get("savescenario")).clicked() {
+                    if !state.scenario_file.ends_with(".json") {
+                        state.scenario_file.push_str(".json");
+                    }
+                    let name = state.scenario_file.trim_end_matches(".json");
+                    let chosen = state.pattern_loader.chosen();
+                    let mut scenario = Scenario::capture(&state.sim, name, (state.camera.pos, state.camera_zoom), state.editor_info.sim_speed, chosen.units);
+                    scenario.description = chosen.description.clone();
+                    scenario.author = chosen.author.clone();
+                    scenario.tags = chosen.tags.clone();
+
+                    state.scenario_status = Some(if PatternLoader::is_builtin(&state.scenario_file) {
+                        format!("{}: {}", state.scenario_file, state.trans.get("builtinname"))
+                    } else {
+                        match files::save(SCENARIO_DIR, &state.scenario_file, &scenario.to_json()) {
+                            Ok(path) => {
+                                state.pattern_loader.add(Pattern::from_scenario(&scenario, &state.scenario_file));
+                                format!("{} {}", state.trans.get("saved"), path)
+                            },
+                            Err(error) => error
+                        }
+                    });
+                }
+                if ui.button(state.trans.get("loadscenario")).clicked() {
+                    if let Err(error) = files::open(SCENARIO_DIR, &state.scenario_file, &state.uploads) {
+                        state.scenario_status = Some(error);
+                    }
+                }
+            });
+            if let Some(status) = &state.scenario_status {
+                ui.label(RichText::new(status).small());
+            }
+            for error in state.pattern_loader.errors.iter() {
+                ui.label(RichText::new(error).color(egui::Color32::LIGHT_RED).small());
+            }
             ui.add_space(20.);
 
-            let slider = Slider::new(&mut state.editor_info.sim_speed, 1..=25).text(state.trans.get("simspeed"));
+            let slider = Slider::new(&mut state.editor_info.sim_speed, 1.0..=1500.0).logarithmic(true).text(state.trans.get("simspeed"));
             ui.add(slider);
+
+            ui.label(state.trans.get("integrator"));
+            let mut force_law_changed = false;
+            ComboBox::from_id_source("integrator").selected_text(state.trans.get(state.sim.integrator.name())).show_ui(ui, |ui| {
+                for integrator in Integrator::ALL {
+                    force_law_changed |= ui.selectable_value(&mut state.sim.integrator, integrator, state.trans.get(integrator.name())).changed();
+                }
+            });
+            let min_softening = state.sim.integrator.min_softening();
+            if state.sim.softening < min_softening {
+                state.sim.softening = min_softening;
+                force_law_changed = true;
+            }
+            let drag_softening = DragValue::new(&mut state.sim.softening).prefix(state.trans.get("softening")).clamp_range(min_softening..=50.0).speed(0.1);
+            force_law_changed |= ui.add(drag_softening).changed();
+            if state.sim.integrator == Integrator::Hermite {
+                ui.horizontal(|ui| {
+                    force_law_changed |= ui.checkbox(&mut state.sim.regularize, state.trans.get("regularize")).changed();
+                    let drag_radius = DragValue::new(&mut state.sim.regularization_radius).clamp_range(1.0..=200.0).speed(0.5);
+                    force_law_changed |= ui.add_enabled(state.sim.regularize, drag_radius).changed();
+                });
+            }
+            if force_law_changed {
+                state.sim.rebase_energy();
+            }
+            ui.add_space(10.);
             
             if ui.checkbox(&mut state.editor_info.show_trail, state.trans.get("showtrail")).clicked() {
                 clear_trail_texture(&mut state.trail_texture, gfx);
             }
             ui.checkbox(&mut state.editor_info.show_bodies, state.trans.get("showbodies"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut state.editor_info.show_potential, state.trans.get("showpotential"));
+                ui.checkbox(&mut state.editor_info.show_contours, state.trans.get("showcontours"));
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut state.editor_info.show_velocity, state.trans.get("showvelocity"));
+                ui.add(DragValue::new(&mut state.editor_info.velocity_scale).prefix("×").clamp_range(0.1..=1000.0).speed(0.5));
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut state.editor_info.show_acceleration, state.trans.get("showacceleration"));
+                ui.add(DragValue::new(&mut state.editor_info.acceleration_scale).prefix("×").clamp_range(1.0..=1000000.0).speed(10.0));
+            });
+            ui.checkbox(&mut state.editor_info.show_contributions, state.trans.get("showcontributions"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut state.editor_info.show_spacetime, state.trans.get("showspacetime"));
+                ui.add(DragValue::new(&mut state.editor_info.spacetime_strength).prefix("×").clamp_range(1.0..=1000000.0).speed(10.0));
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut state.editor_info.show_forecast, state.trans.get("showforecast"));
+                ui.add(DragValue::new(&mut state.editor_info.forecast_time).suffix(format!(" {}", state.trans.get("ticks"))).clamp_range(10..=20000).speed(10.0));
+                if let Some(units) = state.pattern_loader.chosen().units {
+                    ui.label(format!("= {}", time_label(state.editor_info.forecast_time as f64, Some(units), &state.trans)));
+                }
+            });
+            ui.checkbox(&mut state.editor_info.show_plots, state.trans.get("showplots"));
+            ui.checkbox(&mut state.editor_info.show_events, state.trans.get("showevents"));
+            ui.checkbox(&mut state.editor_info.show_section, state.trans.get("showsection"));
+            ui.checkbox(&mut state.editor_info.show_generators, state.trans.get("showgenerators"));
+            ui.checkbox(&mut state.editor_info.show_barycenter, state.trans.get("showbarycenter"));
+            if ui.checkbox(&mut state.track_barycenter, state.trans.get("trackbarycenter")).changed() && state.track_barycenter {
+                state.object_tracking = None;
+            }
+            ui.checkbox(&mut state.editor_info.barycentric_load, state.trans.get("barycentricload"));
+            let zero_btn = ui.add_enabled(state.sim.can_zero_momentum(), Button::new(state.trans.get("zeromomentum")));
+            if zero_btn.on_disabled_hover_text(state.trans.get("fixedanchor")).clicked() {
+                state.sim.zero_momentum();
+                state.sim.reset();
+            }
 
             ui.checkbox(&mut state.editor_info.paused, state.trans.get("pause"));
 
+            let reverse_txt = if state.sim.reversed { state.trans.get("forward") } else { state.trans.get("reverse") };
+            // Only leapfrog is time symmetric, elsewhere the round trip error is mostly integrator error
+            let can_reverse = state.sim.time > 0. && (state.sim.reversed || state.sim.integrator == Integrator::Leapfrog);
+            if ui.add_enabled(can_reverse, Button::new(reverse_txt)).on_disabled_hover_text(state.trans.get("reverseleapfrog")).clicked() {
+                state.sim.reverse();
+            }
+            ui.label(format!("{}: {}", state.trans.get("time"), time_label(state.sim.time, state.pattern_loader.chosen().units, &state.trans)));
+            if let Some(error) = state.sim.round_trip_error {
+                ui.label(format!("{}: {:.4}", state.trans.get("roundtrip"), error));
+            }
+
             ui.checkbox(&mut state.editor_info.editor_enabled, state.trans.get("editor"));
 
             if ui.button(state.trans.get("restart")).clicked() {
                 state.object_tracking = None;
-                state.camera.set_position(0., 0.);
-                state.camera.set_zoom(1.0);
-                state.camera_zoom = 1.;
-                state.pattern_loader.reload_pattern(&mut state.planets);
+                state.pattern_loader.reset_camera(&mut state.camera, &mut state.camera_zoom);
+                state.pattern_loader.reload_pattern(&mut state.sim);
+                state.orbit_parents.clear();
+                if state.editor_info.barycentric_load {
+                    state.sim.to_barycentric();
+                }
+                state.sim.reset();
                 clear_trail_texture(&mut state.trail_texture, gfx);
             }
 
+            ui.horizontal(|ui| {
+                ui.add(DragValue::new(&mut state.sim.seed).prefix(state.trans.get("seed")));
+                if ui.button(state.trans.get("newseed")).clicked() {
+                    state.sim.seed = random::<u32>() as u64;
+                }
+            });
+            ui.label(state.trans.get("seedhint"));
+
+            ui.add_space(10.);
+            ui.collapsing(state.trans.get("conservation"), |ui| {
+                let diagnostics = state.sim.diagnostics();
+                let drift = state.sim.energy_drift(&diagnostics);
+                if state.pattern_loader.chosen().units.is_some() {
+                    ui.label(RichText::new(state.trans.get("simunits")).small());
+                }
+                ui.label(format!("{}: {:.4}", state.trans.get("kinetic"), diagnostics.kinetic));
+                ui.label(format!("{}: {:.4}", state.trans.get("potential"), diagnostics.potential));
+                ui.label(format!("{}: {:.4}", state.trans.get("energy"), diagnostics.energy()));
+                ui.label(format!("{}: {:.3e}", state.trans.get("drift"), drift));
+                ui.label(format!("{} (x, y): ({:.4}, {:.4})", state.trans.get("momentum"), diagnostics.momentum.x, diagnostics.momentum.y));
+                ui.label(format!("{}: {:.4}", state.trans.get("angmomentum"), diagnostics.angular_momentum));
+
+                let mut logging = state.diagnostics_log.is_some();
+                if ui.checkbox(&mut logging, format!("{} {}", state.trans.get("logto"), DIAGNOSTICS_LOG)).changed() {
+                    state.diagnostics_log = None;
+                    state.log_error = None;
+                    if logging {
+                        match DiagnosticsLog::create(DIAGNOSTICS_LOG) {
+                            Ok(log) => state.diagnostics_log = Some(log),
+                            Err(err) => state.log_error = Some(err.to_string())
+                        }
+                    }
+                }
+
+                #[cfg(target_arch = "wasm32")]
+                if let Some(log) = &state.diagnostics_log {
+                    if ui.button(format!("{} {}", state.trans.get("export"), DIAGNOSTICS_LOG)).clicked() {
+                        state.log_error = files::save(".", DIAGNOSTICS_LOG, log.to_csv()).err();
+                    }
+                }
+                if let Some(err) = &state.log_error {
+                    ui.colored_label(egui::Color32::RED, err);
+                }
+            });
+
+            ui.collapsing(state.trans.get("chaos"), |ui| {
+                let mut shadow = state.sim.shadow.is_some();
+                if ui.checkbox(&mut shadow, state.trans.get("shadow")).changed() {
+                    state.sim.set_shadow(shadow);
+                }
+                if let Some(shadow) = &state.sim.shadow {
+                    if let Some(divergence) = shadow.divergence(&state.sim.planets) {
+                        ui.label(format!("ln(d / d0): {:.3}", divergence));
+                    }
+                    if let Some(exponent) = shadow.exponent(&state.sim.planets) {
+                        ui.label(format!("{}: {:.3e}", state.trans.get("lyapunov"), exponent));
+                    }
+                    ui.label(state.trans.get("shadowhint"));
+                }
+            });
+
+            ui.collapsing(state.trans.get("escapers"), |ui| {
+                ComboBox::from_id_source("escapepolicy").selected_text(state.trans.get(state.sim.escape_policy.name())).show_ui(ui, |ui| {
+                    for policy in EscapePolicy::ALL {
+                        ui.selectable_value(&mut state.sim.escape_policy, policy, state.trans.get(policy.name()));
+                    }
+                });
+                ui.add(DragValue::new(&mut state.sim.escape_radius).prefix(state.trans.get("escaperadius")).clamp_range(10.0..=100000.0).speed(10.0));
+                ui.label(format!("{}: {}", state.trans.get("removed"), state.sim.removed));
+            });
+
             ui.add_space(20.);
             ui.label(state.trans.get("wasd"));
             ui.label(state.trans.get("qe"));
 
             // Removing objects from sim
             ui.separator();
-            if state.planets.len() > 0 {
+            if state.sim.planets.len() > 0 {
                 ui.label(RichText::new(state.trans.get("bodiesinsim")).size(15.));
                 if state.object_tracking.is_some() {
                     if ui.button(state.trans.get("untrack")).clicked() {
@@ -226,8 +662,15 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
                 }
                 
                 ScrollArea::vertical().auto_shrink([false, false]).max_width(f32::INFINITY).max_height(app.window().height() as f32 / 1.5).show(ui, |ui| {
-                    for i in 0..state.planets.len() {
-                        ui.label(format!("{} {}", state.trans.get("obj"), i));
+                    for i in 0..state.sim.planets.len() {
+                        let planet = state.sim.planets.get(i).unwrap();
+                        if planet.frozen {
+                            ui.label(format!("{} {} ({})", state.trans.get("obj"), planet.id, state.trans.get("frozen")));
+                        } else if planet.escaped {
+                            ui.label(format!("{} {} ({})", state.trans.get("obj"), planet.id, state.trans.get("escaped")));
+                        } else {
+                            ui.label(format!("{} {}", state.trans.get("obj"), planet.id));
+                        }
 
                         let mut breakloop = false;
 
@@ -236,16 +679,19 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
                             let trackbtn =  ui.button(state.trans.get("track"));
                             if removebtn.clicked() {
                                 state.object_tracking = None;
-                                state.planets.remove(i);
+                                state.sim.planets.remove(i);
+                                state.orbit_parents.clear();
+                                state.sim.reset();
                                 breakloop = true;
                             } else if removebtn.hovered() || trackbtn.hovered() {
-                                state.planets.get_mut(i).unwrap().selected = true;
+                                state.sim.planets.get_mut(i).unwrap().selected = true;
                             } else {
-                                state.planets.get_mut(i).unwrap().selected = false;
+                                state.sim.planets.get_mut(i).unwrap().selected = false;
                             }
 
                             if trackbtn.clicked() {
                                 state.object_tracking = Some(i);
+                                state.track_barycenter = false;
                             }
                         });
 
@@ -253,9 +699,16 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
                             break;
                         }
                         
-                        ui.label(format!("{} (x, y): ({:.1}, {:.1})", state.trans.get("vel"), state.planets.get(i).unwrap().vel.x, state.planets.get(i).unwrap().vel.y));
-                        ui.label(format!("{} (x, y): ({}, {})", state.trans.get("pos"), state.planets.get(i).unwrap().pos.x as i32, state.planets.get(i).unwrap().pos.y as i32));
-                        ui.label(format!("{}: {}", state.trans.get("mass"), state.planets.get(i).unwrap().m));
+                        ui.label(format!("{} (x, y): ({:.1}, {:.1})", state.trans.get("vel"), state.sim.planets.get(i).unwrap().vel.x, state.sim.planets.get(i).unwrap().vel.y));
+                        ui.label(format!("{} (x, y): ({}, {})", state.trans.get("pos"), state.sim.planets.get(i).unwrap().pos.x as i32, state.sim.planets.get(i).unwrap().pos.y as i32));
+                        ui.label(format!("{}: {}", state.trans.get("mass"), state.sim.planets.get(i).unwrap().m));
+                        if state.sim.integrator == Integrator::Hermite && state.sim.planets.get(i).unwrap().apply_forces {
+                            let level = state.sim.planets.get(i).unwrap().level;
+                            ui.label(format!("{}: {} (dt = 1/{})", state.trans.get("steplevel"), level, 1u32 << level.min(MAX_LEVEL)));
+                        }
+                        if state.sim.planets.get(i).unwrap().apply_forces {
+                            orbit_readout(ui, state, i);
+                        }
                         
 
                         ui.add_space(20.);
@@ -281,30 +734,572 @@ fn draw(app: &mut App, gfx: &mut Graphics, plugins: &mut Plugins, state: &mut St
 
                 ui.checkbox(&mut state.new_body.moveable, state.trans.get("moveable"));
 
-                let drag_dir = DragValue::new(&mut state.new_body.dir).prefix(state.trans.get("dir")).clamp_range(0.0..=360.0).speed(1.0);
-                ui.add(drag_dir);
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut state.new_body.from_orbit, false, state.trans.get("atmouse"));
+                    ui.radio_value(&mut state.new_body.from_orbit, true, state.trans.get("onorbit"));
+                });
+
+                if state.new_body.from_orbit {
+                    add_body_on_orbit(ui, state);
+                } else {
+                    let drag_dir = DragValue::new(&mut state.new_body.dir).prefix(state.trans.get("dir")).clamp_range(0.0..=360.0).speed(1.0);
+                    ui.add(drag_dir);
+
+                    let drag_force = DragValue::new(&mut state.new_body.force).prefix(state.trans.get("initf")).clamp_range(0.000001..=10000.0).speed(0.1);
+                    ui.add(drag_force);
+                }
 
-                let drag_force = DragValue::new(&mut state.new_body.force).prefix(state.trans.get("initf")).clamp_range(0.000001..=10000.0).speed(0.1);
-                ui.add(drag_force);
+                if state.new_body.moveable {
+                    ui.add(DragValue::new(&mut state.new_body.preview_ticks).prefix(state.trans.get("previewticks")).clamp_range(0..=5000));
+                }
 
                 ui.add_space(20.);
 
                 if ui.button(state.trans.get("clear")).clicked() {
-                    state.planets.clear();
+                    state.sim.planets.clear();
+                    state.orbit_parents.clear();
+                    state.sim.reseed();
+                    state.sim.reset();
                     clear_trail_texture(&mut state.trail_texture, gfx);
                 }
 
                 let pos = get_mouse_in_world(&(app.mouse.x, app.mouse.y), (app.window().width(), app.window().height()), &state.camera);
                 ui.label(format!("{} (x, y): ({}, {})", state.trans.get("pos"), pos.0 as i32, pos.1 as i32));
 
-                ui.label(state.trans.get("rclick"));
+                if !state.new_body.from_orbit {
+                    ui.label(state.trans.get("rclick"));
+                }
             });
         }
+
+        Window::new(state.trans.get("plots")).open(&mut state.editor_info.show_plots).show(ctx, |ui| {
+            let last_body = state.sim.planets.iter().map(|p| p.id).max().unwrap_or(0);
+            ui.horizontal(|ui| {
+                ComboBox::from_id_source("quantity").selected_text(state.trans.get(state.new_series.name())).show_ui(ui, |ui| {
+                    for quantity in Quantity::ALL {
+                        if ui.selectable_label(state.new_series.same_kind(&quantity), state.trans.get(quantity.name())).clicked() {
+                            state.new_series = quantity;
+                        }
+                    }
+                });
+                match &mut state.new_series {
+                    Quantity::Distance(i, j) => {
+                        ui.add(DragValue::new(i).clamp_range(0..=last_body));
+                        ui.add(DragValue::new(j).clamp_range(0..=last_body));
+                    }
+                    Quantity::Speed(i) => {
+                        ui.add(DragValue::new(i).clamp_range(0..=last_body));
+                    }
+                    _ => ()
+                }
+                if ui.button(state.trans.get("addplot")).clicked() {
+                    state.sim.history.add(state.new_series);
+                }
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                let mut removed = None;
+                for (i, series) in state.sim.history.series.iter().enumerate() {
+                    if ui.button(format!("{} ✖", series_label(&series.quantity, &state.trans))).clicked() {
+                        removed = Some(i);
+                    }
+                }
+                if let Some(i) = removed {
+                    state.sim.history.series.remove(i);
+                }
+            });
+            ui.add(DragValue::new(&mut state.sim.history.interval).prefix(state.trans.get("plotinterval")).clamp_range(1..=1000));
+
+            let units = state.pattern_loader.chosen().units;
+            let time_unit = units.map_or("ticks", |_| "days");
+            ui.label(RichText::new(format!("x: {} ({})", state.trans.get("time"), state.trans.get(time_unit))).small());
+            Plot::new("history").legend(Legend::default()).height(250.).show(ui, |plot_ui| {
+                for series in state.sim.history.series.iter() {
+                    let points = series.samples.iter().map(|&[t, y]| [units.map_or(t, |u| u.days(t)), y]).collect::<PlotPoints>();
+                    plot_ui.line(Line::new(points).name(series_label(&series.quantity, &state.trans)));
+                }
+            });
+        });
+
+        Window::new(state.trans.get("events")).open(&mut state.editor_info.show_events).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut state.sim.detect_events, state.trans.get("detectevents")).changed() && state.sim.detect_events {
+                    state.sim.events.resync(&state.sim.planets, state.sim.integrator, state.sim.softening);
+                }
+                ui.add(DragValue::new(&mut state.sim.events.approach_distance).prefix(state.trans.get("approachdist")).clamp_range(0.1..=1000.0).speed(0.5));
+            });
+            ui.horizontal(|ui| {
+                if ui.button(format!("{} {}", state.trans.get("export"), EVENTS_CSV)).clicked() {
+                    state.export_status = Some(match files::save(".", EVENTS_CSV, &state.sim.events.to_csv()) {
+                        Ok(path) => format!("{} {}", state.trans.get("saved"), path),
+                        Err(error) => error
+                    });
+                }
+                if let Some(status) = &state.export_status {
+                    ui.label(status);
+                }
+            });
+            ui.separator();
+
+            let mut jump = None;
+            let row_height = ui.text_style_height(&egui::TextStyle::Body);
+            let units = state.pattern_loader.chosen().units;
+            ScrollArea::vertical().stick_to_bottom(true).auto_shrink([false, false]).max_height(300.).show_rows(ui, row_height, state.sim.events.events.len(), |ui, rows| {
+                for event in state.sim.events.events.range(rows) {
+                    if ui.selectable_label(false, event_label(event, units, &state.trans)).clicked() {
+                        jump = Some(*event);
+                    }
+                }
+            });
+
+            let find = |id: usize| state.sim.planets.iter().find(|p| p.id == id).map(|p| p.pos);
+            if let Some(mut pos) = jump.and_then(|e| find(e.body)) {
+                if let Some(other) = jump.and_then(|e| e.other).and_then(find) {
+                    pos = (pos + other) / 2.;
+                }
+                state.object_tracking = None;
+                state.track_barycenter = false;
+                state.camera.set_position(pos.x, pos.y);
+            }
+        });
+
+        Window::new(state.trans.get("section")).open(&mut state.editor_info.show_section).show(ctx, |ui| {
+            let last_id = state.sim.planets.iter().map(|p| p.id).max().unwrap_or(0);
+            let section = &mut state.sim.section;
+            let mut changed = false;
+
+            ui.checkbox(&mut state.sim.record_section, state.trans.get("recordsection"));
+            ui.horizontal(|ui| {
+                changed |= ui.add(DragValue::new(&mut section.crossing).prefix(state.trans.get("crossingbody")).clamp_range(0..=last_id)).changed();
+                ComboBox::from_id_source("axis").selected_text(state.trans.get(section.axis.name())).show_ui(ui, |ui| {
+                    for axis in Axis::ALL {
+                        changed |= ui.selectable_value(&mut section.axis, axis, state.trans.get(axis.name())).changed();
+                    }
+                });
+                ComboBox::from_id_source("direction").selected_text(state.trans.get(section.direction.name())).show_ui(ui, |ui| {
+                    for direction in Direction::ALL {
+                        changed |= ui.selectable_value(&mut section.direction, direction, state.trans.get(direction.name())).changed();
+                    }
+                });
+            });
+            ui.horizontal(|ui| {
+                changed |= ui.add(DragValue::new(&mut section.recorded).prefix(state.trans.get("recordedbody")).clamp_range(0..=last_id)).changed();
+                let origin_txt = match section.origin {
+                    Some(id) => format!("{} {}", state.trans.get("obj"), id),
+                    None => state.trans.get("worldorigin")
+                };
+                ui.label(state.trans.get("relativeto"));
+                ComboBox::from_id_source("origin").selected_text(origin_txt).show_ui(ui, |ui| {
+                    changed |= ui.selectable_value(&mut section.origin, None, state.trans.get("worldorigin")).changed();
+                    for planet in state.sim.planets.iter() {
+                        changed |= ui.selectable_value(&mut section.origin, Some(planet.id), format!("{} {}", state.trans.get("obj"), planet.id)).changed();
+                    }
+                });
+            });
+            if changed {
+                section.clear();
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(format!("{}: {}", state.trans.get("points"), section.points.len()));
+                if ui.button(state.trans.get("clearpoints")).clicked() {
+                    section.clear();
+                }
+                if ui.button(format!("{} {}", state.trans.get("export"), SECTION_CSV)).clicked() {
+                    state.export_status = Some(match files::save(".", SECTION_CSV, &section.to_csv()) {
+                        Ok(path) => format!("{} {}", state.trans.get("saved"), path),
+                        Err(error) => error
+                    });
+                }
+            });
+            if let Some(status) = &state.export_status {
+                ui.label(status);
+            }
+
+            let along = if section.axis == Axis::X { "x" } else { "y" };
+            Plot::new("section").height(300.).data_aspect(1.).show(ui, |plot_ui| {
+                let points = section.points.iter().copied().collect::<PlotPoints>();
+                plot_ui.points(Points::new(points).radius(1.5).name(format!("{}, v{}", along, along)));
+            });
+        });
+
+        let mut show_generators = state.editor_info.show_generators;
+        Window::new(state.trans.get("generators")).open(&mut show_generators).resizable(false).show(ctx, |ui| {
+            generator_settings(ui, state);
+            ui.add_space(10.);
+            if ui.button(state.trans.get("generate")).clicked() {
+                generate_bodies(state);
+                clear_trail_texture(&mut state.trail_texture, gfx);
+            }
+        });
+        state.editor_info.show_generators = show_generators;
     });
     
     gfx.render(&output);
 }
 
+/// Writes a row to the diagnostics log, if one is open, for the tick just simulated.
+fn log_diagnostics(state: &mut State) {
+    if let Some(log) = state.diagnostics_log.as_mut() {
+        let diagnostics = state.sim.diagnostics();
+        if let Err(err) = log.write(state.sim.time, &diagnostics, state.sim.energy_drift(&diagnostics)) {
+            state.log_error = Some(err.to_string());
+            state.diagnostics_log = None;
+        }
+    }
+}
+
+fn generator_settings(ui: &mut egui::Ui, state: &mut State) {
+    let trans = &state.trans;
+    let settings = &mut state.generator;
+
+    ComboBox::from_id_source("generator").selected_text(trans.get(settings.generator.name())).show_ui(ui, |ui| {
+        for generator in Generator::ALL {
+            ui.selectable_value(&mut settings.generator, generator, trans.get(generator.name()));
+        }
+    });
+
+    if settings.generator != Generator::Lattice {
+        ui.add(DragValue::new(&mut settings.count).prefix(trans.get("bodycount")).clamp_range(1..=2000));
+    }
+    ui.add(DragValue::new(&mut settings.mass).prefix(trans.get("totalmass")).clamp_range(0.01..=10000.0).speed(0.5));
+
+    match settings.generator {
+        Generator::Plummer => {
+            ui.add(DragValue::new(&mut settings.radius).prefix(trans.get("scaleradius")).clamp_range(1.0..=10000.0));
+        },
+        Generator::King => {
+            ui.add(DragValue::new(&mut settings.inner_radius).prefix(trans.get("coreradius")).clamp_range(1.0..=10000.0));
+            ui.add(DragValue::new(&mut settings.radius).prefix(trans.get("tidalradius")).clamp_range(1.0..=10000.0));
+            ui.label(RichText::new(trans.get("kingvelocities")).small());
+        },
+        Generator::Disk => {
+            ui.add(DragValue::new(&mut settings.inner_radius).prefix(trans.get("innerradius")).clamp_range(0.0..=10000.0));
+            ui.add(DragValue::new(&mut settings.radius).prefix(trans.get("outerradius")).clamp_range(1.0..=10000.0));
+        },
+        Generator::Ring => {
+            ui.add(DragValue::new(&mut settings.radius).prefix(trans.get("ringradius")).clamp_range(1.0..=10000.0));
+        },
+        Generator::Clusters => {
+            ui.add(DragValue::new(&mut settings.clusters).prefix(trans.get("clustercount")).clamp_range(1..=50));
+            ui.add(DragValue::new(&mut settings.inner_radius).prefix(trans.get("clusterradius")).clamp_range(1.0..=10000.0));
+            ui.add(DragValue::new(&mut settings.radius).prefix(trans.get("regionradius")).clamp_range(1.0..=10000.0));
+        },
+        Generator::Lattice => {
+            ui.horizontal(|ui| {
+                ui.add(DragValue::new(&mut settings.columns).prefix(trans.get("columns")).clamp_range(1..=50));
+                ui.add(DragValue::new(&mut settings.rows).prefix(trans.get("rows")).clamp_range(1..=50));
+            });
+            ui.add(DragValue::new(&mut settings.spacing).prefix(trans.get("spacing")).clamp_range(1.0..=1000.0));
+            ui.add(DragValue::new(&mut settings.jitter).prefix(trans.get("jitter")).clamp_range(0.0..=1000.0));
+        },
+        Generator::Galaxies => {
+            ui.add(DragValue::new(&mut settings.core_mass).prefix(trans.get("coremass")).clamp_range(0.01..=10000.0).speed(0.5));
+            ui.add(DragValue::new(&mut settings.inner_radius).prefix(trans.get("innerradius")).clamp_range(0.0..=10000.0));
+            ui.add(DragValue::new(&mut settings.radius).prefix(trans.get("outerradius")).clamp_range(1.0..=10000.0));
+            ui.add(DragValue::new(&mut settings.mass_ratio).prefix(trans.get("massratio")).clamp_range(0.01..=100.0).speed(0.01));
+            ui.add(DragValue::new(&mut settings.separation).prefix(trans.get("separation")).clamp_range(0.0..=100000.0));
+            ui.add(DragValue::new(&mut settings.impact_parameter).prefix(trans.get("impactparameter")).clamp_range(-100000.0..=100000.0));
+            ui.add(DragValue::new(&mut settings.approach_speed).prefix(trans.get("approachspeed")).clamp_range(0.0..=100.0).speed(0.05));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut settings.retrograde[0], trans.get("firstretrograde"));
+                ui.checkbox(&mut settings.retrograde[1], trans.get("secondretrograde"));
+            });
+            if state.sim.integrator == Integrator::Euler {
+                ui.label(RichText::new(trans.get("galaxiesleapfrog")).small());
+            }
+        }
+    }
+
+    match settings.generator {
+        Generator::Plummer | Generator::King | Generator::Clusters => {
+            ui.add(DragValue::new(&mut settings.virial_ratio).prefix(trans.get("targetvirial")).clamp_range(0.0..=2.0).speed(0.01));
+        },
+        Generator::Disk | Generator::Ring => {
+            ui.horizontal(|ui| {
+                ui.add(DragValue::new(&mut settings.central_mass).prefix(trans.get("centralmass")).clamp_range(0.0..=10000.0).speed(0.5));
+                ui.checkbox(&mut settings.central_fixed, trans.get("fixed"));
+            });
+        },
+        Generator::Lattice | Generator::Galaxies => {}
+    }
+
+    ui.horizontal(|ui| {
+        ui.add(DragValue::new(&mut settings.seed).prefix(trans.get("seed")));
+        if ui.button(trans.get("newseed")).clicked() {
+            settings.seed = random::<u32>() as u64;
+        }
+    });
+    ui.checkbox(&mut settings.keep_existing, trans.get("keepexisting"));
+}
+
+/// Replaces the bodies, or adds to them, with generated ones and continues in the playground.
+/// The seed of the generator becomes the seed of the simulation.
+fn generate_bodies(state: &mut State) {
+    if !state.generator.keep_existing {
+        state.sim.planets.clear();
+        state.orbit_parents.clear();
+    }
+    // The Euler law pulls as hard at any distance beyond a few radii, so it has no tides
+    if state.generator.generator == Generator::Galaxies && state.sim.integrator == Integrator::Euler {
+        state.sim.integrator = Integrator::Leapfrog;
+    }
+    state.sim.seed = state.generator.seed;
+    state.sim.reseed();
+
+    for body in state.generator.generate(&mut state.sim.rng, state.sim.integrator, state.sim.softening) {
+        let trail_color = state.sim.random_trail_color();
+        state.sim.planets.push(body.set_trail_color(trail_color));
+    }
+
+    // Switch to the playground without loading it
+    state.pattern_loader.pattern = 0;
+    state.pattern_loader.chosen_pattern = 0;
+    state.editor_info.editor_enabled = true;
+    state.object_tracking = None;
+    state.sim.reset();
+}
+
+fn add_body_on_orbit(ui: &mut egui::Ui, state: &mut State) {
+    let len = state.sim.planets.len();
+    if len == 0 {
+        ui.label(state.trans.get("noparent"));
+        return;
+    }
+    state.new_body.parent = state.new_body.parent.min(len - 1);
+
+    ui.horizontal(|ui| {
+        ui.label(state.trans.get("parent"));
+        ComboBox::from_id_source("newparent").selected_text(format!("{} {}", state.trans.get("obj"), state.sim.planets[state.new_body.parent].id)).show_ui(ui, |ui| {
+            for j in 0..len {
+                ui.selectable_value(&mut state.new_body.parent, j, format!("{} {}", state.trans.get("obj"), state.sim.planets[j].id));
+            }
+        });
+    });
+    ui.add(DragValue::new(&mut state.new_body.a).prefix("a: ").clamp_range(1.0..=100000.0).speed(1.0));
+    ui.add(DragValue::new(&mut state.new_body.e).prefix("e: ").clamp_range(0.0..=0.99).speed(0.005));
+    ui.add(DragValue::new(&mut state.new_body.arg_periapsis).prefix("ω: ").suffix("°").clamp_range(0.0..=360.0).speed(1.0));
+    ui.add(DragValue::new(&mut state.new_body.true_anomaly).prefix(state.trans.get("trueanomaly")).suffix("°").clamp_range(0.0..=360.0).speed(1.0));
+
+    let body = orbit_body(state);
+    if state.sim.integrator == Integrator::Euler && body.is_some_and(|b| b.vel.x.abs() > 10. || b.vel.y.abs() > 10.) {
+        ui.label(RichText::new(state.trans.get("eulerspeedlimit")).small());
+    }
+
+    if let Some(body) = body.filter(|_| ui.button(state.trans.get("placebody")).clicked()) {
+        let trail_color = state.sim.random_trail_color();
+        state.sim.planets.push(body.set_trail_color(trail_color));
+        state.orbit_parents.insert(len, state.new_body.parent);
+        state.sim.reset();
+    }
+}
+
+/// Keeps the forecast on the hovered or tracked body, starting over when the body changes.
+fn update_forecast(state: &mut State) {
+    let target = state.object_tracking.or_else(|| state.sim.planets.iter().position(|p| p.selected))
+        .filter(|&i| state.editor_info.show_forecast && state.sim.planets[i].apply_forces && !state.sim.planets[i].frozen);
+    let Some(i) = target else {
+        state.forecast = None;
+        return;
+    };
+
+    let ticks = state.editor_info.forecast_time;
+    let mut forecast = state.forecast.take()
+        .filter(|f| f.id == Some(state.sim.planets[i].id) && !f.outdated(&state.sim))
+        .unwrap_or_else(|| Forecast::new(&state.sim, i, ticks));
+    forecast.update(&state.sim, ticks, FORECAST_BUDGET, ticks / FORECAST_REFRESH);
+    state.forecast = Some(forecast);
+}
+
+/// Keeps the path of the body about to be added up to date. It starts over at once when the
+/// body or its settings change, once the simulation moved on the new body is picked up by
+/// the next refresh.
+fn update_preview(app: &mut App, state: &mut State) {
+    let body = if !state.editor_info.editor_enabled || !state.new_body.moveable {
+        None
+    } else if state.new_body.from_orbit {
+        orbit_body(state)
+    } else {
+        Some(mouse_body(app, state))
+    };
+    let Some(body) = body else {
+        state.preview = None;
+        return;
+    };
+
+    let ticks = state.new_body.preview_ticks;
+    let mut preview = match state.preview.take().filter(|p| p.ticks == ticks && !p.outdated(&state.sim)) {
+        Some(preview) if preview.follows(&body) => preview,
+        Some(mut preview) if preview.latest().1 != state.sim.time => {
+            preview.set_body(body);
+            preview
+        },
+        _ => Forecast::with_body(&state.sim, body, ticks)
+    };
+    preview.update(&state.sim, ticks, PREVIEW_BUDGET, 1);
+    state.preview = Some(preview);
+}
+
+/// The body a right click would add at the mouse.
+fn mouse_body(app: &mut App, state: &State) -> Mover {
+    let force = vec_math::from_angle(state.new_body.dir * PI / 180.) * state.new_body.force;
+    let mouse_pos = get_mouse_in_world(&(app.mouse.x, app.mouse.y), (app.window().width(), app.window().height()), &state.camera);
+    Mover::new(mouse_pos.0, mouse_pos.1, state.new_body.mass, force.x, force.y).apply_forces(state.new_body.moveable)
+}
+
+/// The body on the orbit set up in the editor, `None` without a parent.
+fn orbit_body(state: &State) -> Option<Mover> {
+    let info = &state.new_body;
+    let parent = state.sim.planets.get(info.parent)?;
+    let parent_vel = if parent.apply_forces { parent.vel } else { Vec2::ZERO };
+    // A fixed parent doesn't fall towards the body, only its own mass pulls them together
+    let mu = G as f64 * if parent.apply_forces { parent.m + info.mass } else { parent.m } as f64;
+
+    let (r, v) = orbit::state_vectors_in(state.sim.integrator, state.sim.softening, info.a as f64, info.e as f64, (info.arg_periapsis as f64).to_radians(), (info.true_anomaly as f64).to_radians(), mu);
+    let pos = parent.pos + r.as_vec2();
+    let vel = parent_vel + v.as_vec2();
+    Some(Mover::new(pos.x, pos.y, info.mass, vel.x, vel.y).apply_forces(info.moveable))
+}
+
+/// Path drawn as dashes, every other group of segments is left out.
+fn draw_dashed(draw: &mut Draw, path: &[Vec2], color: Color, zoom: f32) {
+    const DASH: usize = 4;
+    for start in (0..path.len().saturating_sub(1)).step_by(2 * DASH) {
+        for segment in path[start..].windows(2).take(DASH) {
+            draw.line((segment[0].x, segment[0].y), (segment[1].x, segment[1].y)).color(color).width(1.5 / zoom);
+        }
+    }
+}
+
+fn orbit_readout(ui: &mut egui::Ui, state: &mut State, i: usize) {
+    let len = state.sim.planets.len();
+    let chosen = state.orbit_parents.get(&i).copied().filter(|&p| p != i && p < len);
+    let parent = chosen.or_else(|| orbit::dominant_attractor(&state.sim.planets, i));
+
+    let parent_txt = match (chosen, parent) {
+        (Some(p), _) => format!("{} {}", state.trans.get("obj"), state.sim.planets[p].id),
+        (None, Some(p)) => format!("{} ({} {})", state.trans.get("auto"), state.trans.get("obj"), state.sim.planets[p].id),
+        (None, None) => state.trans.get("auto")
+    };
+    ui.horizontal(|ui| {
+        ui.label(state.trans.get("parent"));
+        ComboBox::from_id_source(("parent", i)).selected_text(parent_txt).show_ui(ui, |ui| {
+            if ui.selectable_label(chosen.is_none(), state.trans.get("auto")).clicked() {
+                state.orbit_parents.remove(&i);
+            }
+            for j in (0..len).filter(|&j| j != i) {
+                if ui.selectable_label(chosen == Some(j), format!("{} {}", state.trans.get("obj"), state.sim.planets[j].id)).clicked() {
+                    state.orbit_parents.insert(i, j);
+                }
+            }
+        });
+    });
+
+    if let Some(elements) = parent.and_then(|p| Elements::of_in(&state.sim.planets, i, p, state.sim.integrator, state.sim.softening)) {
+        ui.label(format!("a = {:.1}, e = {:.3}, ω = {:.1}°", elements.a, elements.e, elements.arg_periapsis.to_degrees()));
+        match (elements.apoapsis, elements.period) {
+            (Some(apoapsis), Some(period)) => {
+                ui.label(format!("{}: {:.1} / {:.1}", state.trans.get("apsides"), elements.periapsis, apoapsis));
+                ui.label(format!("{}: {:.1}, {}", state.trans.get("period"), period, state.trans.get("bound")));
+            }
+            _ => {
+                ui.label(format!("{}: {:.1}", state.trans.get("periapsis"), elements.periapsis));
+                ui.label(state.trans.get("unbound"));
+            }
+        }
+    }
+}
+
+/// Simulated time of `ticks` in the units of the scenario, or in ticks when it has none.
+fn time_label(ticks: f64, units: Option<Units>, trans: &Translations) -> String {
+    match units {
+        Some(units) => {
+            let (time, unit) = units.elapsed(ticks);
+            format!("{:.1} {}", time, trans.get(unit))
+        },
+        None => format!("{} {}", ticks, trans.get("ticks"))
+    }
+}
+
+fn event_label(event: &Event, units: Option<Units>, trans: &Translations) -> String {
+    let bodies = match event.other {
+        Some(other) => format!("{} {}, {}", trans.get("obj"), event.body, other),
+        None => format!("{} {}", trans.get("obj"), event.body)
+    };
+    let details = match event.kind {
+        EventKind::CloseApproach { min_distance, relative_speed } => format!(", d = {:.2}, v = {:.2}", min_distance, relative_speed),
+        EventKind::Periapsis { distance } => format!(", d = {:.2}", distance),
+        EventKind::Unbound => String::new(),
+        EventKind::Escaped(policy) => format!(", {}", trans.get(policy.name()))
+    };
+    format!("t = {}: {} ({}){}", time_label(event.time, units, trans), trans.get(event.kind.name()), bodies, details)
+}
+
+fn series_label(quantity: &Quantity, trans: &Translations) -> String {
+    match quantity {
+        Quantity::Distance(i, j) => format!("{} {}-{}", trans.get(quantity.name()), i, j),
+        Quantity::Speed(i) => format!("{} {}", trans.get(quantity.name()), i),
+        _ => trans.get(quantity.name())
+    }
+}
+
+fn draw_spacetime(draw: &mut Draw, state: &State) {
+    let size = state.camera.work_size / state.camera.scale();
+    let origin = state.camera.pos - size / 2.;
+    // Powers of two keep the lines from sliding around while zooming
+    let spacing = 2f32.powf((SPACETIME_CELL / state.camera_zoom).log2().round());
+    let grid = WarpedGrid::new(&state.sim.planets, state.sim.integrator, state.sim.softening, origin, size, spacing, state.editor_info.spacetime_strength);
+
+    let color = Color::from_rgba(0.4, 0.6, 1., 0.4);
+    for row in 0..grid.rows {
+        for column in 0..grid.columns {
+            let node = grid.node(column, row);
+            if column + 1 < grid.columns {
+                let right = grid.node(column + 1, row);
+                draw.line((node.x, node.y), (right.x, right.y)).color(color).width(1. / state.camera_zoom);
+            }
+            if row + 1 < grid.rows {
+                let below = grid.node(column, row + 1);
+                draw.line((node.x, node.y), (below.x, below.y)).color(color).width(1. / state.camera_zoom);
+            }
+        }
+    }
+}
+
+fn draw_vectors(draw: &mut Draw, state: &State) {
+    let info = &state.editor_info;
+    let planets = &state.sim.planets;
+
+    for (i, planet) in planets.iter().enumerate().filter(|(_, p)| p.apply_forces && !p.frozen) {
+        let pos = planet.render_pos(info.accumulator);
+        if info.show_velocity {
+            draw_arrow(draw, pos, planet.vel * info.velocity_scale, Color::GREEN, state.camera_zoom);
+        }
+        if info.show_acceleration || info.show_contributions {
+            let contributions = field::accelerations(planets, i, state.sim.integrator, state.sim.softening);
+            if info.show_contributions && (planet.selected || state.object_tracking == Some(i)) {
+                for (_, acc) in contributions.iter() {
+                    draw_arrow(draw, pos, *acc * info.acceleration_scale, Color::from_rgba(1., 0.6, 0.2, 0.7), state.camera_zoom);
+                }
+            }
+            if info.show_acceleration {
+                let acc = contributions.iter().map(|(_, acc)| *acc).sum::<Vec2>();
+                draw_arrow(draw, pos, acc * info.acceleration_scale, Color::RED, state.camera_zoom);
+            }
+        }
+    }
+}
+
+fn draw_arrow(draw: &mut Draw, from: Vec2, vector: Vec2, color: Color, zoom: f32) {
+    let to = from + vector;
+    let head = vector.normalize_or_zero() * (vector.length() * 0.3).min(10. / zoom);
+    let side = head.perp() * 0.5;
+
+    draw.line((from.x, from.y), (to.x, to.y)).color(color).width(1.5 / zoom);
+    draw.line((to.x, to.y), (to.x - head.x + side.x, to.y - head.y + side.y)).color(color).width(1.5 / zoom);
+    draw.line((to.x, to.y), (to.x - head.x - side.x, to.y - head.y - side.y)).color(color).width(1.5 / zoom);
+}
+
 fn clear_trail_texture(trail_tex: &mut RenderTexture, gfx: &mut Graphics) {
     let mut draw = trail_tex.create_draw();
     draw.clear(Color::from_hex(0x252526FF));
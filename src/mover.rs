@@ -1,38 +1,71 @@
 use std::ops::AddAssign;
 
-use notan::{math::Vec2, draw::{Draw, DrawShapes}, prelude::{Color, App}, random::rand::random};
+use notan::{math::{Vec2, DVec2}, draw::{Draw, DrawShapes}, prelude::Color};
 
 use crate::{vec_math, TRAIL_TEX_WIDTH, G, TRAIL_TEX_HEIGHT};
 
+/// Id of a body the simulation hasn't numbered yet.
+pub const NO_ID: usize = usize::MAX;
+
 #[derive(Clone, Copy)]
 pub struct Mover {
     pub pos: Vec2,
+    pub prev_pos: Vec2,
     pub delta_pos: Vec2,
     pub vel: Vec2,
     pub acc: Vec2,
+    pub jerk: Vec2,
+    pub level: u32,
     pub m: f32,
     pub r: f32,
     pub trail_color: Color,
     pub apply_forces: bool,
-    pub selected: bool
+    pub selected: bool,
+    /// Number the simulation gave the body when it was added, it stays with the body when
+    /// others are added or removed.
+    pub id: usize,
+    /// Unbound from the system and beyond the escape radius.
+    pub escaped: bool,
+    /// Takes no part in the simulation, neither moving nor attracting.
+    pub frozen: bool,
+    /// Position and velocity in double precision as leapfrog left them.
+    pub exact: (DVec2, DVec2)
 }
 
 impl Mover {
     pub fn new(x: f32, y: f32, m: f32, fx: f32, fy: f32) -> Self {
         Self {
             pos: Vec2::new(x, y),
+            prev_pos: Vec2::new(x, y),
             delta_pos: Vec2::new(x, y),
             vel: Vec2::new(fx, fy),
             acc: Vec2::ZERO,
+            jerk: Vec2::ZERO,
+            level: 0,
             m,
             r: m.sqrt() * 10.,
-            trail_color: Color::from_rgb(0.5 + random::<f32>() / 2., 0.5 + random::<f32>() / 2., 0.5 + random::<f32>() / 2.),
+            trail_color: Color::WHITE,
             apply_forces: true,
-            selected: false
+            selected: false,
+            id: NO_ID,
+            escaped: false,
+            frozen: false,
+            exact: (DVec2::new(x as f64, y as f64), DVec2::new(fx as f64, fy as f64))
+        }
+    }
+
+    /// Position and velocity in double precision, unless `pos` or `vel` were changed since
+    /// they were rounded from it.
+    pub fn exact_state(&self) -> (DVec2, DVec2) {
+        let (pos, vel) = self.exact;
+        if pos.as_vec2() == self.pos && vel.as_vec2() == self.vel {
+            (pos, vel)
+        } else {
+            (self.pos.as_dvec2(), self.vel.as_dvec2())
         }
     }
 
-    pub fn update(&mut self, _app: &mut App) {
+    pub fn update(&mut self) {
         if self.apply_forces {
             self.vel.add_assign(self.acc);
             self.pos.add_assign(self.vel);
@@ -42,19 +75,29 @@ impl Mover {
         }
     }
 
-    pub fn save_delta_pos(&mut self) {
-        self.delta_pos = self.pos.clone();
+    /// Position between the last two ticks, `alpha` is how far into the next tick the
+    /// real time accumulator already is.
+    pub fn render_pos(&self, alpha: f32) -> Vec2 {
+        self.prev_pos.lerp(self.pos, alpha)
     }
 
-    pub fn render_trail(&self, draw: &mut Draw) {
-        draw.line(((TRAIL_TEX_WIDTH as f32 / 2. + self.pos.x), (TRAIL_TEX_HEIGHT as f32 / 2. + self.pos.y)), ((TRAIL_TEX_WIDTH as f32 / 2. + self.delta_pos.x), (TRAIL_TEX_HEIGHT as f32 / 2. + self.delta_pos.y))).width(2.).color(self.trail_color);
+    pub fn save_delta_pos(&mut self, alpha: f32) {
+        self.delta_pos = self.render_pos(alpha);
     }
 
-    pub fn render(&self, draw: &mut Draw) {
-        if !self.selected {
-            draw.ellipse((self.pos.x, self.pos.y), (self.r, self.r));
+    pub fn render_trail(&self, draw: &mut Draw, alpha: f32) {
+        let pos = self.render_pos(alpha);
+        draw.line(((TRAIL_TEX_WIDTH as f32 / 2. + pos.x), (TRAIL_TEX_HEIGHT as f32 / 2. + pos.y)), ((TRAIL_TEX_WIDTH as f32 / 2. + self.delta_pos.x), (TRAIL_TEX_HEIGHT as f32 / 2. + self.delta_pos.y))).width(2.).color(self.trail_color);
+    }
+
+    pub fn render(&self, draw: &mut Draw, alpha: f32) {
+        let pos = self.render_pos(alpha);
+        if self.selected {
+            draw.ellipse((pos.x, pos.y), (self.r, self.r)).color(Color::RED);
+        } else if self.escaped {
+            draw.ellipse((pos.x, pos.y), (self.r, self.r)).color(Color::ORANGE);
         } else {
-            draw.ellipse((self.pos.x, self.pos.y), (self.r, self.r)).color(Color::RED);
+            draw.ellipse((pos.x, pos.y), (self.r, self.r));
         }
     }
 
@@ -69,6 +112,12 @@ impl Mover {
         self
     }
 
+    pub fn set_trail_color(mut self, color: Color) -> Self {
+        self.trail_color = color;
+
+        self
+    }
+
     pub fn set_vel(mut self, x: f32, y: f32) -> Self {
         self.vel.x = x;
         self.vel.y = y;
@@ -76,10 +125,10 @@ impl Mover {
         self
     }
 
-    pub fn attract(&self, mover: &mut Mover) {
+    pub fn attract(&self, mover: &mut Mover, softening: f32) {
         if mover.apply_forces {
             let mut force = self.pos.clone() - mover.pos.clone();
-            let distance_sq = vec_math::mag_sq(&force).clamp(25., 2500.);
+            let distance_sq = vec_math::mag_sq(&force).clamp(softening * softening, 2500.);
 
             let strength = G * (self.m * mover.m) / distance_sq;
 
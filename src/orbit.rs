@@ -0,0 +1,246 @@
+use std::f64::consts::PI;
+
+use notan::math::{DVec2, Vec2};
+
+use crate::{mover::Mover, integrator::Integrator, diagnostics::pair_potential, vec_math, G};
+
+/// Osculating Keplerian elements of a body around its parent, the orbit the body would
+/// follow if every other body vanished and the force was not softened.
+pub struct Elements {
+    /// Semi-major axis, negative for hyperbolic orbits.
+    pub a: f64,
+    pub e: f64,
+    /// Argument of periapsis in radians, measured like the editor's direction.
+    pub arg_periapsis: f64,
+    pub periapsis: f64,
+    /// Only bound orbits have an apoapsis and a period.
+    pub apoapsis: Option<f64>,
+    pub period: Option<f64>
+}
+
+impl Elements {
+    /// Elements of a body at `r` with velocity `v` relative to its parent, with
+    /// `mu = G * (m_parent + m_body)`.
+    pub fn from_state(r: DVec2, v: DVec2, mu: f64) -> Option<Self> {
+        let dist = r.length();
+        if dist == 0. || mu <= 0. {
+            return None;
+        }
+
+        let h = r.perp_dot(v);
+        let e_vec = (r * (v.length_squared() - mu / dist) - v * r.dot(v)) / mu;
+        let e = e_vec.length();
+        let a = -mu / (v.length_squared() - 2. * mu / dist);
+        let bound = e < 1.;
+
+        Some(Self {
+            a,
+            e,
+            arg_periapsis: if e > 0. { e_vec.y.atan2(e_vec.x) } else { 0. },
+            periapsis: h * h / (mu * (1. + e)),
+            apoapsis: bound.then_some(a * (1. + e)),
+            period: bound.then(|| 2. * PI * (a * a * a / mu).sqrt())
+        })
+    }
+
+    /// Elements of body `i` around body `parent`. Bodies that ignore forces count as
+    /// standing still.
+    pub fn of(planets: &[Mover], i: usize, parent: usize) -> Option<Self> {
+        let (body, parent) = (planets.get(i)?, planets.get(parent)?);
+        let vel = |p: &Mover| if p.apply_forces { p.vel.as_dvec2() } else { DVec2::ZERO };
+
+        let r = (body.pos - parent.pos).as_dvec2();
+        Self::from_state(r, vel(body) - vel(parent), G as f64 * (body.m + parent.m) as f64)
+    }
+
+    /// Like `of`, but for the force law of `integrator` with `softening`. Orbits there don't
+    /// close, so the apsides are where the body turns around, the period is the time from one
+    /// periapsis to the next and `a` and `e` are those of the ellipse with the same apsides.
+    /// Escaping bodies get their Keplerian elements.
+    pub fn of_in(planets: &[Mover], i: usize, parent: usize, integrator: Integrator, softening: f32) -> Option<Self> {
+        let keplerian = Self::of(planets, i, parent)?;
+        if (integrator != Integrator::Euler && softening == 0.) || !bound_to(planets, i, parent, integrator, softening) {
+            return Some(keplerian);
+        }
+
+        let (body, parent) = (&planets[i], &planets[parent]);
+        let r = (body.pos - parent.pos).as_dvec2();
+        let v = (velocity(body) - velocity(parent)).as_dvec2();
+        let mu = G as f64 * (body.m + parent.m) as f64;
+        let potential = |rho: f64| pair_potential(integrator, mu, rho, softening as f64);
+
+        // Left over radial kinetic energy, negative beyond the turning points
+        let dist = r.length();
+        let h = r.perp_dot(v);
+        let energy = v.length_squared() / 2. + potential(dist);
+        let radial_energy = |rho: f64| energy - potential(rho) - h * h / (2. * rho * rho);
+
+        let periapsis = turning_point(radial_energy, dist * 1e-9, dist);
+        let mut far = dist;
+        while radial_energy(far) >= 0. && far.is_finite() {
+            far *= 2.;
+        }
+        let apoapsis = turning_point(radial_energy, far, dist);
+
+        let radial_speed = |rho: f64| (2. * radial_energy(rho)).max(1e-12).sqrt();
+        let mid = (periapsis + apoapsis) / 2.;
+        let half = |f: &dyn Fn(f64) -> f64| from_turning_point(periapsis, mid, f).abs() + from_turning_point(apoapsis, mid, f).abs();
+        let period = 2. * half(&|rho| 1. / radial_speed(rho));
+
+        // The angle swept since the last periapsis, or until the next one when falling in
+        let turn_rate = |rho: f64| h.abs() / (rho * rho * radial_speed(rho));
+        let swept = h.signum() * if dist <= mid {
+            from_turning_point(periapsis, dist, turn_rate).abs()
+        } else {
+            half(&turn_rate) - from_turning_point(apoapsis, dist, turn_rate).abs()
+        };
+        let angle = r.y.atan2(r.x);
+        let arg_periapsis = if r.dot(v) >= 0. { angle - swept } else { angle + swept };
+
+        Some(Self {
+            a: mid,
+            e: (apoapsis - periapsis) / (apoapsis + periapsis),
+            arg_periapsis: (arg_periapsis + PI).rem_euclid(2. * PI) - PI,
+            periapsis,
+            apoapsis: Some(apoapsis),
+            period: Some(period)
+        })
+    }
+
+    pub fn bound(&self) -> bool {
+        self.e < 1.
+    }
+}
+
+/// Distance between `inside` and `outside` where `radial_energy` runs out, by bisection.
+fn turning_point(radial_energy: impl Fn(f64) -> f64, inside: f64, outside: f64) -> f64 {
+    let (mut lo, mut hi) = (inside, outside);
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.;
+        if radial_energy(mid) < 0. { lo = mid } else { hi = mid }
+    }
+    (lo + hi) / 2.
+}
+
+/// Integral of `f` from the turning point `turn` to `to`. Substituting `turn + (to - turn) s²`
+/// takes out the inverse square root singularity of the radial speed at the turning point.
+fn from_turning_point(turn: f64, to: f64, f: impl Fn(f64) -> f64) -> f64 {
+    const STEPS: usize = 256;
+    let span = to - turn;
+    (0..STEPS).map(|k| {
+        let s = (k as f64 + 0.5) / STEPS as f64;
+        f(turn + span * s * s) * 2. * span * s
+    }).sum::<f64>() / STEPS as f64
+}
+
+/// Position and velocity relative to the parent of a body on an elliptical orbit, `true_anomaly`
+/// is the angle from periapsis. The inverse of `Elements::from_state`.
+pub fn state_vectors(a: f64, e: f64, arg_periapsis: f64, true_anomaly: f64, mu: f64) -> (DVec2, DVec2) {
+    let p = a * (1. - e * e);
+    let (sin_w, cos_w) = vec_math::sin_cos(arg_periapsis);
+    let cos_nu = vec_math::sin_cos(true_anomaly).1;
+    let (sin, cos) = vec_math::sin_cos(arg_periapsis + true_anomaly);
+
+    let r = p / (1. + e * cos_nu);
+    let speed = (mu / p).sqrt();
+    (DVec2::new(cos, sin) * r, DVec2::new(-sin - e * sin_w, cos + e * cos_w) * speed)
+}
+
+/// Like `state_vectors`, but for the force law of `integrator` with `softening`. Other laws
+/// have no closed ellipses, so the body is put at the same place and given the energy and
+/// angular momentum that make it turn around at the periapsis and apoapsis of the ellipse.
+/// The orbit then precesses between those two distances.
+#[allow(clippy::too_many_arguments)]
+pub fn state_vectors_in(integrator: Integrator, softening: f32, a: f64, e: f64, arg_periapsis: f64, true_anomaly: f64, mu: f64) -> (DVec2, DVec2) {
+    let eps = softening as f64;
+    if integrator != Integrator::Euler && eps == 0. {
+        return state_vectors(a, e, arg_periapsis, true_anomaly, mu);
+    }
+
+    let potential = |r: f64| pair_potential(integrator, mu, r, eps);
+    let (sin_nu, cos_nu) = vec_math::sin_cos(true_anomaly);
+    let (sin, cos) = vec_math::sin_cos(arg_periapsis + true_anomaly);
+    let radial = DVec2::new(cos, sin);
+    let r = a * (1. - e * e) / (1. + e * cos_nu);
+
+    let (periapsis, apoapsis) = (a * (1. - e), a * (1. + e));
+    let momentum_sq = if apoapsis - periapsis > 1e-9 * a {
+        2. * (potential(apoapsis) - potential(periapsis)) / (1. / (periapsis * periapsis) - 1. / (apoapsis * apoapsis))
+    } else {
+        // Circular, the pull balances the centrifugal force: L² = r³ dΦ/dr
+        let dr = 1e-6 * a;
+        a * a * a * (potential(a + dr) - potential(a - dr)) / (2. * dr)
+    };
+    let energy = momentum_sq / (2. * periapsis * periapsis) + potential(periapsis);
+
+    let tangential = momentum_sq.max(0.).sqrt() / r;
+    let outward = (2. * (energy - potential(r)) - tangential * tangential).max(0.).sqrt();
+    let outward = if sin_nu < 0. { -outward } else { outward };
+    (radial * r, radial * outward + radial.perp() * tangential)
+}
+
+/// Whether body `i` is bound to its dominant attractor or to the rest of the system, taken
+/// as a single body at its centre of mass. Bodies that ignore forces and the heaviest body,
+/// which anchors the system, always are.
+pub fn bound_to_system(planets: &[Mover], i: usize, integrator: Integrator, softening: f32) -> bool {
+    let body = &planets[i];
+    if !body.apply_forces || body.frozen {
+        return true;
+    }
+    match dominant_attractor(planets, i) {
+        None => return true,
+        Some(p) if bound_to(planets, i, p, integrator, softening) => return true,
+        _ => ()
+    }
+
+    let others = planets.iter().enumerate().filter(|(j, p)| *j != i && !p.frozen).map(|(_, p)| p);
+    let mass = others.clone().map(|p| p.m).sum::<f32>();
+    if mass <= 0. {
+        return false;
+    }
+    let center = others.clone().map(|p| p.pos * p.m).sum::<Vec2>() / mass;
+    let center_vel = others.map(|p| velocity(p) * p.m).sum::<Vec2>() / mass;
+    bound(body.pos - center, body.vel - center_vel, G as f64 * (mass + body.m) as f64, integrator, softening)
+}
+
+/// Whether body `i` can't get away from body `parent` under the force law of `integrator`.
+/// The Euler law keeps pulling however far a body goes, so nothing gets away there.
+pub fn bound_to(planets: &[Mover], i: usize, parent: usize, integrator: Integrator, softening: f32) -> bool {
+    let (body, parent) = (&planets[i], &planets[parent]);
+    bound(body.pos - parent.pos, velocity(body) - velocity(parent), G as f64 * (body.m + parent.m) as f64, integrator, softening)
+}
+
+fn bound(r: Vec2, v: Vec2, mu: f64, integrator: Integrator, softening: f32) -> bool {
+    let eps = softening as f64;
+    let energy = v.length_squared() as f64 / 2. + pair_potential(integrator, mu, r.length() as f64, eps);
+    energy < pair_potential(integrator, mu, f64::INFINITY, eps)
+}
+
+fn velocity(planet: &Mover) -> Vec2 {
+    if planet.apply_forces { planet.vel } else { Vec2::ZERO }
+}
+
+/// The body at least as heavy as body `i` that pulls hardest on it. The heaviest body has none.
+pub fn dominant_attractor(planets: &[Mover], i: usize) -> Option<usize> {
+    let body = planets.get(i)?;
+    planets.iter().enumerate()
+        .filter(|(j, p)| *j != i && !p.frozen && p.m > 0. && p.m >= body.m && p.pos != body.pos)
+        .map(|(j, p)| (j, p.m / p.pos.distance_squared(body.pos)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(j, _)| j)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elements_round_trip() {
+        let (r, v) = state_vectors(300., 0.4, 0.7, 2.1, 50.);
+        let elements = Elements::from_state(r, v, 50.).unwrap();
+        assert!((elements.a - 300.).abs() < 1e-9);
+        assert!((elements.e - 0.4).abs() < 1e-12);
+        assert!((elements.arg_periapsis - 0.7).abs() < 1e-12);
+        assert!((elements.periapsis - 180.).abs() < 1e-9);
+    }
+}
\ No newline at end of file
@@ -1,89 +1,186 @@
-use crate::{mover::Mover, camera::Camera2D};
+use std::fs;
+
+use notan::{math::Vec2, prelude::Color};
+
+use crate::{mover::Mover, camera::Camera2D, integrator::Integrator, scenario::{Scenario, Localized}, simulation::Simulation, units::Units, solar_system};
+
+/// Directory scanned for scenario files at startup.
+pub const SCENARIO_DIR: &str = "scenarios";
+
+/// Scenarios built into the binary, so they are there on the web too. A file with the same
+/// name in `SCENARIO_DIR` takes their place.
+const BUILTIN: [(&str, &str); 7] = [
+    ("01-planet.json", include_str!("../scenarios/01-planet.json")),
+    ("02-binary.json", include_str!("../scenarios/02-binary.json")),
+    ("03-eccentric-binary.json", include_str!("../scenarios/03-eccentric-binary.json")),
+    ("04-four-bodies.json", include_str!("../scenarios/04-four-bodies.json")),
+    ("05-wide-orbit.json", include_str!("../scenarios/05-wide-orbit.json")),
+    ("06-flyby.json", include_str!("../scenarios/06-flyby.json")),
+    ("07-moon.json", include_str!("../scenarios/07-moon.json"))
+];
 
 pub struct Pattern {
+    /// Scenario file the pattern was read from, empty for the playground.
+    pub file: String,
+    pub name: Localized,
+    pub description: Option<Localized>,
+    pub author: Option<String>,
+    pub tags: Vec<String>,
+    pub sim_speed: Option<f32>,
     pub bodies: Vec<Mover>,
+    /// Trail colours given by the scenario, by body. The others are picked with the seed.
+    pub colors: Vec<Option<Color>>,
+    pub seed: Option<u64>,
+    pub softening: Option<f32>,
+    pub integrator: Option<Integrator>,
+    /// Physical units the pattern was given in, simulated time is shown in them.
+    pub units: Option<Units>,
+    /// Camera position and zoom the pattern starts with.
+    pub camera: (Vec2, f32)
 }
 
 impl Pattern {
     pub fn new() -> Self {
         Self {
-            bodies: Vec::<Mover>::new()
+            file: String::new(),
+            name: Localized::Plain(String::new()),
+            description: None,
+            author: None,
+            tags: Vec::new(),
+            sim_speed: None,
+            bodies: Vec::<Mover>::new(),
+            colors: Vec::new(),
+            seed: None,
+            softening: None,
+            integrator: None,
+            units: None,
+            camera: (Vec2::ZERO, 1.)
         }
     }
 
     pub fn add_body(mut self, mover: Mover) -> Self {
         self.bodies.push(mover);
+        self.colors.push(None);
 
         self
-    } 
+    }
+
+    /// `file` names the pattern when the scenario doesn't.
+    pub fn from_scenario(scenario: &Scenario, file: &str) -> Self {
+        let mut pattern = scenario.movers().into_iter().fold(Pattern::new(), Pattern::add_body);
+        pattern.file = file.to_string();
+        pattern.name = scenario.name.clone().unwrap_or_else(|| Localized::Plain(file.trim_end_matches(".json").to_string()));
+        pattern.description = scenario.description.clone();
+        pattern.author = scenario.author.clone();
+        pattern.tags = scenario.tags.clone();
+        pattern.sim_speed = scenario.sim_speed;
+        pattern.colors = scenario.bodies.iter().map(|b| b.color).collect();
+        pattern.seed = scenario.seed;
+        pattern.softening = scenario.softening();
+        pattern.integrator = scenario.integrator;
+        pattern.units = scenario.units;
+        pattern.camera = scenario.camera();
+        pattern
+    }
 }
 
 pub struct PatternLoader {
     pub patterns: Vec<Pattern>,
     pub pattern: usize,
     pub chosen_pattern: usize,
+    /// Loads the chosen pattern again even though it didn't change.
+    pub reload: bool,
+    /// Only patterns with this tag are offered in the selector.
+    pub tag: Option<String>,
+    /// Scenario files that could not be read, with the reason.
+    pub errors: Vec<String>
 }
 
 impl PatternLoader {
     pub fn new() -> Self {
-        let mut patterns = Vec::<Pattern>::new();
-
-        // Pattern 0
-        patterns.push(Pattern::new());
-
-        // Pattern 1
-        patterns.push(Pattern::new()
-                        .add_body(Mover::new(0.0, 0.0, 5., 0., 0.).apply_forces(false))
-                        .add_body(Mover::new(0.0 + 100., 0.0 - 100., 3., 2., 2.)));
-
-        // Pattern 2
-        patterns.push(Pattern::new()
-                        .add_body(Mover::new(0.0 + 0., 0.0 - 150., 3., 1.35, 0.0))
-                        .add_body(Mover::new(0.0 + 0., 0.0 + 150., 3., -1.35, 0.0)));
-
-        // Pattern 3
-        patterns.push(Pattern::new()
-                        .add_body(Mover::new(0.0 + 0., 0.0 - 150., 3., 0.5, 0.0))
-                        .add_body(Mover::new(0.0 + 0., 0.0 + 150., 3., -0.5, 0.0)));
-
-        // Pattern 4
-        patterns.push(Pattern::new()
-                        .add_body(Mover::new(0.0 + 200., 0.0 - 0., 3., 0., 2.))
-                        .add_body(Mover::new(0.0 - 200., 0.0 - 0., 3., 0., -2.))
-                        .add_body(Mover::new(0.0 - 0., 0.0 - 200., 3., 2., 0.))
-                        .add_body(Mover::new(0.0 - 0., 0.0 + 200., 3., -2., 0.)));
-
-        // Pattern 5
-        patterns.push(Pattern::new()
-                        .add_body(Mover::new(0.0, 0.0, 5., 0., 0.).apply_forces(false))
-                        .add_body(Mover::new(0.0 + 400., 0.0 + 0., 1., 0., 0.4)));
-
-        // Pattern 6
-        patterns.push(Pattern::new()
-                        .add_body(Mover::new(0.0 + 0., 0.0 - 150., 3., 2., 0.0))
-                        .add_body(Mover::new(0.0 + 0., 0.0 + 150., 3., -2., 0.0)));
-
-        // Pattern 7
-        patterns.push(Pattern::new()
-                        .add_body(Mover::new(0.0, 0.0, 10., 0., 0.).apply_forces(false))
-                        .add_body(Mover::new(-2050., 0., 1., 0., 9.))
-                        .add_body(Mover::new(-2090., 0., 0.41, 1.0, 9.7)));
+        let mut files = BUILTIN.iter().map(|(name, text)| (name.to_string(), text.to_string())).collect::<Vec<_>>();
+        let mut errors = Vec::new();
+
+        // The directory is optional, on the web there is none
+        if let Ok(entries) = fs::read_dir(SCENARIO_DIR) {
+            let mut paths = entries.filter_map(|e| e.ok()).map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "json"))
+                .collect::<Vec<_>>();
+            paths.sort();
+
+            for path in paths {
+                let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                match fs::read_to_string(&path) {
+                    Ok(text) => match files.iter_mut().find(|(n, _)| *n == name) {
+                        Some(file) => file.1 = text,
+                        None => files.push((name, text))
+                    },
+                    Err(error) => errors.push(format!("{}: {}", name, error))
+                }
+            }
+        }
 
+        // Pattern 0 is the empty playground
+        let mut patterns = vec![Pattern::new()];
+        for (name, text) in files {
+            match Scenario::parse(&text) {
+                Ok(scenario) => patterns.push(Pattern::from_scenario(&scenario, &name)),
+                Err(error) => errors.push(format!("{}: {}", name, error))
+            }
+        }
+        if !patterns.iter().any(|p| p.file == solar_system::FILE) {
+            patterns.push(Pattern::from_scenario(&solar_system::scenario(), solar_system::FILE));
+        }
 
+        // Start on the first scenario, or the playground when none could be read
+        let first = patterns.len().min(2) - 1;
         Self {
             patterns,
-            pattern: 1,
-            chosen_pattern: 1,
+            pattern: first,
+            chosen_pattern: first,
+            reload: false,
+            tag: None,
+            errors
         }
     }
 
+    /// Adds a pattern, or replaces the one read from the same file, and returns its index.
+    pub fn add(&mut self, pattern: Pattern) -> usize {
+        match self.patterns.iter().position(|p| !p.file.is_empty() && p.file == pattern.file) {
+            Some(i) => {
+                self.patterns[i] = pattern;
+                i
+            },
+            None => {
+                self.patterns.push(pattern);
+                self.patterns.len() - 1
+            }
+        }
+    }
+
+    /// Whether `file` is the name of a scenario that ships with the program.
+    pub fn is_builtin(file: &str) -> bool {
+        file == solar_system::FILE || BUILTIN.iter().any(|(name, _)| *name == file)
+    }
+
+    pub fn chosen(&self) -> &Pattern {
+        self.patterns.get(self.chosen_pattern).unwrap()
+    }
+
+    /// Every tag used by a pattern, sorted.
+    pub fn tags(&self) -> Vec<&str> {
+        let mut tags = self.patterns.iter().flat_map(|p| p.tags.iter().map(|t| t.as_str())).collect::<Vec<_>>();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+
     pub fn handle_pattern_changes(&mut self, camera: &mut Camera2D, camera_zoom: &mut f32, editor_enabled: &mut bool) -> bool {
-        if self.chosen_pattern != self.pattern {
+        if self.chosen_pattern != self.pattern || self.reload {
             self.chosen_pattern = self.pattern;
-            camera.set_position(0., 0.);
-            camera.set_zoom(1.0);
-            *camera_zoom = 1.0;
-            
+            self.reload = false;
+            self.reset_camera(camera, camera_zoom);
+
             if self.chosen_pattern != 0 {
                 *editor_enabled = false;
             } else {
@@ -94,21 +191,43 @@ impl PatternLoader {
         false
     }
 
-    pub fn load_pattern(&self, planets: &mut Vec<Mover>, index: usize) {
-        planets.clear();
+    pub fn reset_camera(&self, camera: &mut Camera2D, camera_zoom: &mut f32) {
+        let (pos, zoom) = self.chosen().camera;
+        camera.set_position(pos.x, pos.y);
+        camera.set_zoom(zoom);
+        *camera_zoom = zoom;
+    }
 
-        for mover in &self.patterns.get(index).unwrap().bodies {
-            planets.push(mover.to_owned());
+    /// Takes over the seed, softening and integrator of the chosen pattern, where it sets them.
+    pub fn apply_settings(&self, sim: &mut Simulation) {
+        let pattern = self.chosen();
+        if let Some(seed) = pattern.seed {
+            sim.seed = seed;
         }
-
+        if let Some(softening) = pattern.softening {
+            sim.softening = softening;
+        }
+        if let Some(integrator) = pattern.integrator {
+            sim.integrator = integrator;
+        }
+        sim.softening = sim.softening.max(sim.integrator.min_softening());
     }
 
-    pub fn reload_pattern(&self, planets: &mut Vec<Mover>) {
-        planets.clear();
+    /// Replaces the bodies with those of the chosen pattern and colours their trails,
+    /// the simulation still needs a reset.
+    pub fn reload_pattern(&self, sim: &mut Simulation) {
+        let pattern = self.chosen();
+        sim.planets.clear();
 
-        for mover in &self.patterns.get(self.chosen_pattern).unwrap().bodies {
-            planets.push(mover.to_owned());
+        for mover in &pattern.bodies {
+            sim.planets.push(mover.to_owned());
         }
 
+        sim.reseed();
+        for (planet, color) in sim.planets.iter_mut().zip(&pattern.colors) {
+            if let Some(color) = color {
+                planet.trail_color = *color;
+            }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,153 @@
+use notan::math::Vec2;
+
+use crate::{mover::Mover, simulation::Simulation};
+
+/// Path of one body through a copy of the system integrated ahead of the simulation.
+pub struct Prediction {
+    planets: Vec<Mover>,
+    /// Index of the followed body in `planets`.
+    pub body: usize,
+    /// Position of the body after every predicted tick, starting with where it is now.
+    pub path: Vec<Vec2>,
+    ticks_left: u32,
+    /// Pair interactions granted but not spent yet, a tick is only taken once it is paid for.
+    credit: u64
+}
+
+impl Prediction {
+    pub fn new(planets: Vec<Mover>, body: usize, ticks: u32) -> Self {
+        let path = planets.get(body).map(|p| vec![p.pos]).unwrap_or_default();
+        Self {
+            planets,
+            body,
+            path,
+            ticks_left: ticks,
+            credit: 0
+        }
+    }
+
+    /// Integrates with the settings of `sim` for at most `budget` more pair interactions.
+    /// What a tick costs beyond the budget is carried over, so even ticks that cost more
+    /// than one budget get done after a few calls.
+    pub fn advance(&mut self, sim: &Simulation, budget: u64) {
+        if self.done() {
+            return;
+        }
+
+        self.credit = self.credit.saturating_add(budget);
+        while self.ticks_left > 0 {
+            let cost = sim.integrator.cost(&self.planets);
+            if cost > self.credit {
+                break;
+            }
+            self.credit -= cost;
+            sim.advance_copy(&mut self.planets);
+            self.path.push(self.planets[self.body].pos);
+            self.ticks_left -= 1;
+        }
+    }
+
+    pub fn done(&self) -> bool {
+        self.ticks_left == 0 || self.path.is_empty()
+    }
+}
+
+/// Path of a body through the simulation, integrated within a budget per frame. Once a
+/// prediction is finished it is shown until the next one is, and the next one only starts
+/// after the simulation has moved on by `refresh` ticks.
+pub struct Forecast {
+    /// `Mover::id` of the followed body, or `None` for a body that is not in the simulation.
+    pub id: Option<usize>,
+    /// Body added to the simulation's own for every prediction, the followed one then.
+    extra: Option<Mover>,
+    body: usize,
+    bodies: usize,
+    /// Ticks the latest prediction covers.
+    pub ticks: u32,
+    /// Predictions with the simulation time they started at.
+    shown: Option<(Prediction, f64)>,
+    pending: Option<(Prediction, f64)>
+}
+
+impl Forecast {
+    /// Follows body `i` of the simulation.
+    pub fn new(sim: &Simulation, i: usize, ticks: u32) -> Self {
+        Self::start(sim, i, None, ticks)
+    }
+
+    /// Follows `body` as if it was added to the simulation now.
+    pub fn with_body(sim: &Simulation, body: Mover, ticks: u32) -> Self {
+        Self::start(sim, sim.planets.len(), Some(body), ticks)
+    }
+
+    fn start(sim: &Simulation, body: usize, extra: Option<Mover>, ticks: u32) -> Self {
+        let mut forecast = Self {
+            id: extra.is_none().then(|| sim.planets[body].id),
+            extra,
+            body,
+            bodies: sim.planets.len(),
+            ticks,
+            shown: None,
+            pending: None
+        };
+        forecast.pending = Some(forecast.prediction(sim, ticks));
+        forecast
+    }
+
+    /// Whether the predictions start from `body`, or will once the next one starts.
+    pub fn follows(&self, body: &Mover) -> bool {
+        self.extra.is_some_and(|e| e.pos == body.pos && e.vel == body.vel && e.m == body.m && e.apply_forces == body.apply_forces)
+    }
+
+    /// Predictions from the next one on start from `body`.
+    pub fn set_body(&mut self, body: Mover) {
+        self.extra = Some(body);
+    }
+
+    fn prediction(&mut self, sim: &Simulation, ticks: u32) -> (Prediction, f64) {
+        self.ticks = ticks;
+        let mut planets = sim.planets.clone();
+        planets.extend(self.extra);
+        (Prediction::new(planets, self.body, ticks), sim.time)
+    }
+
+    /// The simulation was reset or bodies were added or removed since the forecast started.
+    pub fn outdated(&self, sim: &Simulation) -> bool {
+        let started = self.pending.as_ref().or(self.shown.as_ref()).map_or(0., |p| p.1);
+        self.bodies != sim.planets.len() || (!sim.reversed && sim.time < started)
+    }
+
+    /// Integrates at most `budget` pair interactions of the pending prediction, or starts
+    /// the next one once the simulation is `refresh` ticks past the shown one.
+    pub fn update(&mut self, sim: &Simulation, ticks: u32, budget: u64, refresh: u32) {
+        match self.pending.as_mut() {
+            Some((prediction, _)) => {
+                prediction.advance(sim, budget);
+                if prediction.done() {
+                    self.shown = self.pending.take();
+                }
+            },
+            None => {
+                let started = self.shown.as_ref().map_or(f64::NEG_INFINITY, |p| p.1);
+                if (sim.time - started).abs() >= refresh.max(1) as f64 {
+                    let mut next = self.prediction(sim, ticks);
+                    next.0.advance(sim, budget);
+                    self.pending = Some(next);
+                }
+            }
+        }
+    }
+
+    /// Predicted positions from the simulation time `time` on, the part of the path that
+    /// was already simulated is left out.
+    pub fn path(&self, time: f64) -> &[Vec2] {
+        let (path, start) = self.latest();
+        &path[((time - start).abs() as usize).min(path.len())..]
+    }
+
+    /// Path of the finished prediction, or of the pending one while there is none, with the
+    /// simulation time it starts at.
+    pub fn latest(&self) -> (&[Vec2], f64) {
+        self.shown.as_ref().or(self.pending.as_ref()).map_or((&[], 0.), |(p, start)| (&p.path, *start))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,186 @@
+use std::f64::consts::PI;
+
+use notan::math::DVec2;
+
+use crate::G;
+
+/// Pairs are only regularized while the tidal pull of the other bodies stays below
+/// this fraction of their mutual attraction.
+const MAX_PERTURBATION: f64 = 0.1;
+/// Fictitious time steps per orbit of the regularized oscillator.
+const STEPS_PER_ORBIT: f64 = 128.;
+/// Bodies closer than this are left to the softened force, at zero separation the
+/// transformation has no direction and the binding energy no bound.
+const MIN_SEPARATION: f64 = 1e-6;
+
+/// A close binary whose relative motion is integrated in Levi-Civita coordinates, the
+/// planar form of the Kustaanheimo-Stiefel transformation. The relative position is
+/// `r = u²` as a complex number and time is stretched by `dt = |r| ds`, which turns the
+/// Kepler singularity into a harmonic oscillator that stays smooth even through a
+/// head-on collision.
+pub struct Pair {
+    pub i: usize,
+    pub j: usize,
+    m_i: f64,
+    m_j: f64,
+    u: DVec2,
+    du: DVec2,
+    h: f64
+}
+
+impl Pair {
+    pub fn new(i: usize, j: usize, pos: &[DVec2], vel: &[DVec2], mass: &[f64]) -> Self {
+        let r = pos[j] - pos[i];
+        let v = vel[j] - vel[i];
+        let u = csqrt(r);
+
+        Self {
+            i,
+            j,
+            m_i: mass[i],
+            m_j: mass[j],
+            u,
+            du: cmul(conj(u), v) / 2.,
+            h: v.length_squared() / 2. - G as f64 * (mass[i] + mass[j]) / r.length()
+        }
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.m_i + self.m_j
+    }
+
+    /// Relative position and velocity of body `j` with respect to body `i`.
+    pub fn relative(&self) -> (DVec2, DVec2) {
+        let r = cmul(self.u, self.u);
+        let v = cmul(self.u, self.du) * 2. / self.u.length_squared();
+        (r, v)
+    }
+
+    /// Positions and velocities of both bodies around the given centre of mass.
+    pub fn split(&self, cm: DVec2, cm_vel: DVec2) -> ((DVec2, DVec2), (DVec2, DVec2)) {
+        let (r, v) = self.relative();
+        let (f_i, f_j) = (self.m_j / self.mass(), self.m_i / self.mass());
+        ((cm - r * f_i, cm_vel - v * f_i), (cm + r * f_j, cm_vel + v * f_j))
+    }
+
+    /// Advances the relative motion by `dt` while the centre of mass drifts from `cm` with
+    /// `cm_vel`. The other bodies perturb the pair from their positions in `pos`.
+    pub fn advance(&mut self, dt: f64, cm: DVec2, cm_vel: DVec2, pos: &[DVec2], mass: &[f64], eps_sq: f64) {
+        let mut t = 0.;
+
+        for _ in 0..1_000_000 {
+            let remaining = dt - t;
+            if remaining.abs() <= dt * 1e-12 {
+                break;
+            }
+
+            // A degenerate pair can't be advanced, it keeps its state rather than turning into NaN
+            let step = self.step_size();
+            if !step.is_finite() || step <= 0. || self.u.length_squared() <= 0. {
+                break;
+            }
+            let ds = (remaining / self.u.length_squared()).clamp(-step, step);
+            t += self.rk4(ds, t, cm, cm_vel, pos, mass, eps_sq);
+        }
+    }
+
+    fn step_size(&self) -> f64 {
+        if self.h < 0. {
+            2. * PI / (-self.h / 2.).sqrt() / STEPS_PER_ORBIT
+        } else {
+            0.05 * (self.u.length_squared() / (G as f64 * self.mass())).sqrt()
+        }
+    }
+
+    /// One Runge-Kutta step in fictitious time, returns the physical time it covered.
+    #[allow(clippy::too_many_arguments)]
+    fn rk4(&mut self, ds: f64, t: f64, cm: DVec2, cm_vel: DVec2, pos: &[DVec2], mass: &[f64], eps_sq: f64) -> f64 {
+        let deriv = |u: DVec2, du: DVec2, h: f64, t: f64| {
+            let r = cmul(u, u);
+            let center = cm + cm_vel * t;
+            let (f_i, f_j) = (self.m_j / self.mass(), self.m_i / self.mass());
+            let p = external_acc(center + r * f_j, (self.i, self.j), pos, mass, eps_sq)
+                - external_acc(center - r * f_i, (self.i, self.j), pos, mass, eps_sq);
+
+            let r_len = u.length_squared();
+            let ddu = u * (h / 2.) + cmul(conj(u), p) * (r_len / 2.);
+            let dh = 2. * cmul(u, du).dot(p);
+            (du, ddu, dh, r_len)
+        };
+
+        let (u, du, h) = (self.u, self.du, self.h);
+        let k1 = deriv(u, du, h, t);
+        let k2 = deriv(u + k1.0 * (ds / 2.), du + k1.1 * (ds / 2.), h + k1.2 * (ds / 2.), t + k1.3 * (ds / 2.));
+        let k3 = deriv(u + k2.0 * (ds / 2.), du + k2.1 * (ds / 2.), h + k2.2 * (ds / 2.), t + k2.3 * (ds / 2.));
+        let k4 = deriv(u + k3.0 * ds, du + k3.1 * ds, h + k3.2 * ds, t + k3.3 * ds);
+
+        self.u += (k1.0 + (k2.0 + k3.0) * 2. + k4.0) * (ds / 6.);
+        self.du += (k1.1 + (k2.1 + k3.1) * 2. + k4.1) * (ds / 6.);
+        self.h += (k1.2 + (k2.2 + k3.2) * 2. + k4.2) * (ds / 6.);
+        (k1.3 + (k2.3 + k3.3) * 2. + k4.3) * (ds / 6.)
+    }
+}
+
+/// Picks bound pairs closer than `radius`, but not right on top of each other, that are weakly perturbed by the rest of the
+/// system, closest pairs first. A body is never part of more than one pair.
+pub fn find_pairs(pos: &[DVec2], vel: &[DVec2], mass: &[f64], moving: &[bool], radius: f64, eps_sq: f64) -> Vec<Pair> {
+    let n = pos.len();
+    let mut candidates = Vec::new();
+    for i in 0..n {
+        for j in i + 1..n {
+            let r = (pos[j] - pos[i]).length();
+            if moving[i] && moving[j] && mass[i] > 0. && mass[j] > 0. && r < radius && r > MIN_SEPARATION {
+                candidates.push((r, i, j));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut taken = vec![false; n];
+    let mut pairs = Vec::new();
+    for (r, i, j) in candidates {
+        if taken[i] || taken[j] {
+            continue;
+        }
+
+        let m = G as f64 * (mass[i] + mass[j]);
+        let bound = (vel[j] - vel[i]).length_squared() / 2. - m / r < 0.;
+        let tidal = (external_acc(pos[j], (i, j), pos, mass, eps_sq) - external_acc(pos[i], (i, j), pos, mass, eps_sq)).length();
+
+        if bound && tidal * r * r < MAX_PERTURBATION * m {
+            taken[i] = true;
+            taken[j] = true;
+            pairs.push(Pair::new(i, j, pos, vel, mass));
+        }
+    }
+
+    pairs
+}
+
+/// Softened acceleration at `x` from every body except the two in `skip`.
+fn external_acc(x: DVec2, skip: (usize, usize), pos: &[DVec2], mass: &[f64], eps_sq: f64) -> DVec2 {
+    let mut acc = DVec2::ZERO;
+    for k in 0..pos.len() {
+        if k != skip.0 && k != skip.1 && mass[k] > 0. {
+            let dr = pos[k] - x;
+            let r_sq = dr.length_squared() + eps_sq;
+            acc += dr * (G as f64 * mass[k] / (r_sq * r_sq.sqrt()));
+        }
+    }
+    acc
+}
+
+fn cmul(a: DVec2, b: DVec2) -> DVec2 {
+    DVec2::new(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x)
+}
+
+fn conj(a: DVec2) -> DVec2 {
+    DVec2::new(a.x, -a.y)
+}
+
+fn csqrt(a: DVec2) -> DVec2 {
+    let len = a.length();
+    let re = ((len + a.x) / 2.).sqrt();
+    let im = ((len - a.x) / 2.).sqrt();
+    DVec2::new(re, if a.y < 0. { -im } else { im })
+}
\ No newline at end of file
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use notan::{math::Vec2, prelude::Color};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+use crate::{mover::Mover, integrator::Integrator, simulation::Simulation, units::Units, G};
+
+/// Largest softening length in pixels. The Euler law clamps distances to between the
+/// softening and 50, so a longer one has no meaning there.
+const MAX_SOFTENING: f32 = 50.;
+
+/// Setup read from a JSON file. Everything but the bodies is optional, what is left out
+/// keeps the current setting of the simulation.
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Shown in the pattern selector, the file name when left out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Localized>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Localized>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Topics the scenario can be picked by.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Recommended simulation speed, in time units per second.
+    pub sim_speed: Option<f32>,
+    pub seed: Option<u64>,
+    /// Gravitational constant the masses are given for, they are rescaled to `G`.
+    #[serde(default = "default_g")]
+    pub g: f32,
+    /// Physical units positions, velocities, masses, the softening and the camera position
+    /// are given in. The constant of the unit system is used then and `g` is ignored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub units: Option<Units>,
+    pub softening: Option<f32>,
+    pub integrator: Option<Integrator>,
+    #[serde(default)]
+    pub camera: View,
+    pub bodies: Vec<Body>
+}
+
+/// Text given either once or per language code, as in `{ "en": "...", "lt": "..." }`.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+pub enum Localized {
+    Plain(String),
+    ByLanguage(HashMap<String, String>)
+}
+
+impl Localized {
+    /// Text in `lang`, falling back to English and then to any language there is.
+    pub fn get(&self, lang: &str) -> &str {
+        match self {
+            Localized::Plain(text) => text,
+            Localized::ByLanguage(texts) => texts.get(lang).or_else(|| texts.get("en")).or_else(|| texts.values().next()).map_or("", |t| t.as_str())
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct View {
+    #[serde(default)]
+    pub x: f32,
+    #[serde(default)]
+    pub y: f32,
+    #[serde(default = "default_zoom")]
+    pub zoom: f32
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self { x: 0., y: 0., zoom: 1. }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Body {
+    pub x: f32,
+    pub y: f32,
+    pub mass: f32,
+    #[serde(default)]
+    pub vx: f32,
+    #[serde(default)]
+    pub vy: f32,
+    /// Stays in place, attracting the others without being attracted.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub fixed: bool,
+    /// Drawn radius in pixels, worked out from the mass when left out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f32>,
+    /// Trail colour as "#rrggbb", picked with the seed when left out.
+    #[serde(default, deserialize_with = "hex_color", serialize_with = "to_hex_color", skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>
+}
+
+impl Scenario {
+    /// Syntax errors carry the line and column they were found at, values the simulation
+    /// can't run with are reported after that.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let scenario = serde_json::from_str::<Self>(text).map_err(|e| e.to_string())?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let positive = |value: f64| value.is_finite() && value > 0.;
+
+        if let Some(units) = self.units {
+            if !positive(units.scale) || !positive(units.step) {
+                return Err("units: scale and step must be positive".to_string());
+            }
+        } else if !positive(self.g as f64) {
+            return Err("g must be positive".to_string());
+        }
+        if self.sim_speed.is_some_and(|s| !positive(s as f64)) {
+            return Err("sim_speed must be positive".to_string());
+        }
+        let min_softening = self.integrator.map_or(0., |i| i.min_softening());
+        if self.softening().is_some_and(|s| !(min_softening..=MAX_SOFTENING).contains(&s)) {
+            return Err(format!("softening must be between {} and {} pixels", min_softening, MAX_SOFTENING));
+        }
+        let (pos, zoom) = self.camera();
+        if !pos.is_finite() || !positive(zoom as f64) {
+            return Err("camera: zoom must be positive".to_string());
+        }
+
+        for (i, (body, mover)) in self.bodies.iter().zip(self.movers()).enumerate() {
+            if !positive(body.mass as f64) || !positive(mover.m as f64) {
+                return Err(format!("body {}: mass must be positive", i));
+            }
+            if !mover.pos.is_finite() || !mover.vel.is_finite() || !mover.r.is_finite() || mover.r < 0. {
+                return Err(format!("body {}: position, velocity and radius must be finite numbers", i));
+            }
+        }
+        Ok(())
+    }
+
+    /// The bodies and settings of `sim` as they are now, frozen bodies are left out. With
+    /// `units` they are written in physical units.
+    pub fn capture(sim: &Simulation, name: &str, camera: (Vec2, f32), sim_speed: f32, units: Option<Units>) -> Self {
+        let length = |x: f32| units.map_or(x, |u| u.from_length(x) as f32);
+        let velocity = |v: f32| units.map_or(v, |u| u.from_velocity(v) as f32);
+        let mass = |m: f32| units.map_or(m, |u| u.from_mass(m) as f32);
+
+        let bodies = sim.planets.iter().filter(|p| !p.frozen).map(|p| Body {
+            x: length(p.pos.x),
+            y: length(p.pos.y),
+            mass: mass(p.m),
+            vx: velocity(p.vel.x),
+            vy: velocity(p.vel.y),
+            fixed: !p.apply_forces,
+            radius: (p.r != Mover::new(0., 0., p.m, 0., 0.).r).then_some(p.r),
+            color: Some(p.trail_color)
+        }).collect();
+
+        Self {
+            name: Some(Localized::Plain(name.to_string())),
+            description: None,
+            author: None,
+            tags: Vec::new(),
+            sim_speed: Some(sim_speed),
+            seed: Some(sim.seed),
+            g: G,
+            units,
+            softening: Some(length(sim.softening)),
+            integrator: Some(sim.integrator),
+            camera: View { x: length(camera.0.x), y: length(camera.0.y), zoom: camera.1 },
+            bodies
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn camera(&self) -> (Vec2, f32) {
+        (Vec2::new(self.length(self.camera.x), self.length(self.camera.y)), self.camera.zoom)
+    }
+
+    pub fn softening(&self) -> Option<f32> {
+        self.softening.map(|s| self.length(s))
+    }
+
+    /// Bodies of the scenario, with the masses rescaled so they pull as hard under `G` as
+    /// they would under the constant of the file or of its units.
+    pub fn movers(&self) -> Vec<Mover> {
+        self.bodies.iter().map(|b| {
+            let mut mover = match self.units {
+                Some(units) => Mover::new(units.length(b.x as f64), units.length(b.y as f64), units.mass(b.mass as f64), units.velocity(b.vx as f64), units.velocity(b.vy as f64)),
+                None => Mover::new(b.x, b.y, b.mass * self.g / G, b.vx, b.vy)
+            };
+            if let Some(radius) = b.radius {
+                mover.r = radius;
+            }
+            mover.apply_forces(!b.fixed)
+        }).collect()
+    }
+
+    /// Length in pixels of `x`, given in the units of the scenario.
+    fn length(&self, x: f32) -> f32 {
+        self.units.map_or(x, |u| u.length(x as f64))
+    }
+}
+
+fn default_g() -> f32 {
+    G
+}
+
+fn default_zoom() -> f32 {
+    1.
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+fn to_hex_color<S: Serializer>(color: &Option<Color>, serializer: S) -> Result<S::Ok, S::Error> {
+    let [r, g, b, _] = color.unwrap_or(Color::WHITE).rgba_u8();
+    serializer.serialize_str(&format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+fn hex_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Color>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    let digits = text.strip_prefix('#').unwrap_or(&text);
+    match u32::from_str_radix(digits, 16) {
+        Ok(rgb) if digits.len() == 6 => Ok(Some(Color::from_hex(rgb << 8 | 0xFF))),
+        _ => Err(D::Error::custom(format!("invalid colour \"{}\", expected \"#rrggbb\"", text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn syntax_errors_carry_their_position() {
+        let error = Scenario::parse("{\n  \"bodies\": [\n    { \"x\": }\n  ]\n}").err().unwrap();
+        assert!(error.contains("line 3 column"), "{}", error);
+    }
+
+    #[test]
+    fn bodies_need_a_positive_mass() {
+        let error = Scenario::parse(r#"{ "bodies": [{ "x": 0, "y": 0, "mass": 0, "vx": 0, "vy": 0 }] }"#).err().unwrap();
+        assert_eq!(error, "body 0: mass must be positive");
+    }
+}
\ No newline at end of file
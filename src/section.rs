@@ -0,0 +1,145 @@
+use std::fmt::Write;
+
+use notan::math::Vec2;
+
+use crate::mover::Mover;
+
+/// Line of the surface of section, through the origin.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y
+}
+
+impl Axis {
+    pub const ALL: [Axis; 2] = [Axis::X, Axis::Y];
+
+    /// Translation key of the axis.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Axis::X => "xaxis",
+            Axis::Y => "yaxis"
+        }
+    }
+
+    /// Signed distance from the line.
+    fn across(&self, v: Vec2) -> f32 {
+        match self {
+            Axis::X => v.y,
+            Axis::Y => v.x
+        }
+    }
+
+    /// Coordinate along the line.
+    fn along(&self, v: Vec2) -> f32 {
+        match self {
+            Axis::X => v.x,
+            Axis::Y => v.y
+        }
+    }
+}
+
+/// Which crossings count, by the sign of the crossing body's velocity across the line.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Increasing,
+    Decreasing,
+    Both
+}
+
+impl Direction {
+    pub const ALL: [Direction; 3] = [Direction::Increasing, Direction::Decreasing, Direction::Both];
+
+    /// Translation key of the direction.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Increasing => "increasing",
+            Direction::Decreasing => "decreasing",
+            Direction::Both => "both"
+        }
+    }
+}
+
+/// Records the phase space coordinates of one body every time another body crosses a line.
+/// Bodies are referred to by their `Mover::id`, positions are taken relative to the origin
+/// body, or to the world origin when there is none.
+pub struct Section {
+    pub crossing: usize,
+    pub recorded: usize,
+    pub origin: Option<usize>,
+    pub axis: Axis,
+    pub direction: Direction,
+    /// Position along the line and velocity along the line of the recorded body.
+    pub points: Vec<[f64; 2]>,
+    /// Most points kept, recording stops once there are this many.
+    pub capacity: usize,
+    /// Distance of the crossing body from the line and the recorded point at the last tick.
+    last: Option<(f32, [f64; 2])>
+}
+
+impl Section {
+    pub fn new() -> Self {
+        Self {
+            crossing: 0,
+            recorded: 0,
+            origin: None,
+            axis: Axis::X,
+            direction: Direction::Increasing,
+            points: Vec::new(),
+            capacity: 100000,
+            last: None
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.last = None;
+    }
+
+    /// Distance of the crossing body from the line and the point of the recorded body, `None`
+    /// when one of the bodies doesn't exist.
+    fn measure(&self, planets: &[Mover]) -> Option<(f32, [f64; 2])> {
+        let find = |id: usize| planets.iter().find(|p| p.id == id);
+        let (origin, origin_vel) = match self.origin {
+            Some(id) => find(id).map(|o| (o.pos, if o.apply_forces { o.vel } else { Vec2::ZERO }))?,
+            None => (Vec2::ZERO, Vec2::ZERO)
+        };
+        let (crossing, recorded) = (find(self.crossing)?, find(self.recorded)?);
+
+        let across = self.axis.across(crossing.pos - origin);
+        Some((across, [self.axis.along(recorded.pos - origin) as f64, self.axis.along(recorded.vel - origin_vel) as f64]))
+    }
+
+    /// Looks for a crossing after a tick.
+    pub fn update(&mut self, planets: &[Mover]) {
+        let now = self.measure(planets);
+        if let (Some((last_across, last_point)), Some((across, point))) = (self.last, now) {
+            let counts = match self.direction {
+                Direction::Increasing => last_across < 0. && across >= 0.,
+                Direction::Decreasing => last_across > 0. && across <= 0.,
+                Direction::Both => (last_across < 0.) != (across < 0.)
+            };
+
+            if counts && self.points.len() < self.capacity {
+                // Interpolate to where the line was crossed between the two ticks
+                let t = (last_across / (last_across - across)) as f64;
+                self.points.push([last_point[0] + (point[0] - last_point[0]) * t, last_point[1] + (point[1] - last_point[1]) * t]);
+            }
+        }
+        self.last = now;
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("position,velocity\n");
+        for point in self.points.iter() {
+            let _ = writeln!(csv, "{},{}", point[0], point[1]);
+        }
+        csv
+    }
+}
+
+impl Default for Section {
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,332 @@
+use std::collections::HashSet;
+
+use notan::{prelude::Color, random::{utils::Random, rand::Rng}, math::Vec2};
+
+use crate::{mover::{Mover, NO_ID}, integrator::Integrator, diagnostics::Diagnostics, history::History, events::EventLog, orbit, chaos::Shadow, section::Section};
+
+/// What happens to bodies that escape the system.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum EscapePolicy {
+    Ignore,
+    Flag,
+    /// Stops simulating the body but keeps it on screen.
+    Freeze,
+    Remove
+}
+
+impl EscapePolicy {
+    pub const ALL: [EscapePolicy; 4] = [EscapePolicy::Ignore, EscapePolicy::Flag, EscapePolicy::Freeze, EscapePolicy::Remove];
+
+    /// Translation key of the policy.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EscapePolicy::Ignore => "ignore",
+            EscapePolicy::Flag => "flag",
+            EscapePolicy::Freeze => "freeze",
+            EscapePolicy::Remove => "removebody"
+        }
+    }
+}
+
+pub struct Simulation {
+    pub planets: Vec<Mover>,
+    pub integrator: Integrator,
+    pub softening: f32,
+    pub regularize: bool,
+    pub regularization_radius: f32,
+    /// Simulated ticks since the clock was last reset, counts down while reversed.
+    pub time: f64,
+    pub reversed: bool,
+    /// Largest distance between a body and its starting point after a run back to the start.
+    pub round_trip_error: Option<f32>,
+    /// Seed of `rng`, every random choice made for the bodies comes from this generator.
+    pub seed: u64,
+    pub rng: Random,
+    /// Total energy the drift is measured against.
+    pub initial_energy: f64,
+    pub history: History,
+    pub detect_events: bool,
+    pub events: EventLog,
+    pub escape_policy: EscapePolicy,
+    /// Distance from the barycenter beyond which unbound bodies count as escaped.
+    pub escape_radius: f32,
+    /// Bodies removed by the escape policy since the last reset.
+    pub removed: usize,
+    /// Perturbed copy of the system used to measure chaos, see `set_shadow`.
+    pub shadow: Option<Shadow>,
+    pub record_section: bool,
+    pub section: Section,
+    initial: Vec<Mover>,
+    next_id: usize
+}
+
+impl Simulation {
+    pub fn new() -> Self {
+        Self {
+            planets: Vec::<Mover>::new(),
+            integrator: Integrator::Euler,
+            softening: 5.,
+            regularize: true,
+            regularization_radius: 20.,
+            time: 0.,
+            reversed: false,
+            round_trip_error: None,
+            seed: 0,
+            rng: Random::new(0),
+            initial_energy: 0.,
+            history: History::new(),
+            detect_events: false,
+            events: EventLog::new(),
+            escape_policy: EscapePolicy::Flag,
+            escape_radius: 3000.,
+            removed: 0,
+            shadow: None,
+            record_section: false,
+            section: Section::new(),
+            initial: Vec::<Mover>::new(),
+            next_id: 0
+        }
+    }
+
+    /// Takes the current bodies as the new initial conditions and restarts the clock.
+    pub fn reset(&mut self) {
+        self.time = 0.;
+        self.reversed = false;
+        self.round_trip_error = None;
+        self.removed = 0;
+        self.number_bodies();
+        self.initial = self.planets.clone();
+        self.restart_shadow();
+        self.rebase_energy();
+        self.history.clear();
+        self.sample_history();
+        self.events.clear(&self.planets, self.integrator, self.softening);
+        self.section.clear();
+    }
+
+    /// Numbers the bodies added since the last reset, and copies of bodies that were. Ids
+    /// start over when every body is new.
+    fn number_bodies(&mut self) {
+        if self.planets.iter().all(|p| p.id == NO_ID) {
+            self.next_id = 0;
+        }
+        let mut seen = HashSet::new();
+        for planet in self.planets.iter_mut() {
+            if planet.id == NO_ID || !seen.insert(planet.id) {
+                planet.id = self.next_id;
+                self.next_id += 1;
+            }
+        }
+    }
+
+    fn sample_history(&mut self) {
+        let diagnostics = self.history.needs_diagnostics().then(|| {
+            let diagnostics = self.diagnostics();
+            (diagnostics, self.energy_drift(&diagnostics))
+        });
+        let divergence = self.shadow.as_ref().and_then(|s| s.divergence(&self.planets));
+        self.history.record(self.time, &self.planets, diagnostics, divergence);
+    }
+
+    /// Starts or stops following a shadow trajectory.
+    pub fn set_shadow(&mut self, enabled: bool) {
+        self.shadow = enabled.then(|| Shadow::new(&self.planets));
+    }
+
+    fn restart_shadow(&mut self) {
+        self.set_shadow(self.shadow.is_some());
+    }
+
+    /// Measures the energy drift from now on, needed whenever the force law changes.
+    pub fn rebase_energy(&mut self) {
+        self.initial_energy = self.diagnostics().energy();
+    }
+
+    pub fn diagnostics(&self) -> Diagnostics {
+        Diagnostics::measure(&self.planets, self.integrator, self.softening, self.regularize.then_some(self.regularization_radius))
+    }
+
+    /// Relative change of the total energy since the last reset, 0 when it started at 0.
+    pub fn energy_drift(&self, diagnostics: &Diagnostics) -> f64 {
+        if self.initial_energy == 0. {
+            return 0.;
+        }
+        (diagnostics.energy() - self.initial_energy) / self.initial_energy.abs()
+    }
+
+    /// Restarts the generator from `seed` and gives every body a fresh trail colour from it.
+    pub fn reseed(&mut self) {
+        self.rng.reseed(self.seed);
+        for i in 0..self.planets.len() {
+            self.planets[i].trail_color = self.random_trail_color();
+        }
+    }
+
+    pub fn random_trail_color(&mut self) -> Color {
+        Color::from_rgb(0.5 + self.rng.gen::<f32>() / 2., 0.5 + self.rng.gen::<f32>() / 2., 0.5 + self.rng.gen::<f32>() / 2.)
+    }
+
+    /// Steps a copy of the bodies by one tick with the settings of the simulation.
+    pub fn advance_copy(&self, planets: &mut [Mover]) {
+        advance(planets, self.integrator, self.softening, self.regularize.then_some(self.regularization_radius));
+    }
+
+    /// Mass-weighted centre of all bodies as drawn at `alpha`, see `Mover::render_pos`.
+    pub fn barycenter(&self, alpha: f32) -> Option<Vec2> {
+        let mass = self.planets.iter().map(|p| p.m).sum::<f32>();
+        if mass <= 0. {
+            return None;
+        }
+        Some(self.planets.iter().map(|p| p.render_pos(alpha) * p.m).sum::<Vec2>() / mass)
+    }
+
+    /// Bodies with mass that are held in place anchor the frame, the total momentum can
+    /// only be removed without them.
+    pub fn can_zero_momentum(&self) -> bool {
+        !self.planets.iter().any(|p| !p.apply_forces && p.m > 0.)
+    }
+
+    /// Subtracts the centre of mass velocity from every body.
+    pub fn zero_momentum(&mut self) {
+        if !self.can_zero_momentum() {
+            return;
+        }
+
+        let mass = self.planets.iter().map(|p| p.m).sum::<f32>();
+        if mass > 0. {
+            let vel = self.planets.iter().map(|p| p.vel * p.m).sum::<Vec2>() / mass;
+            for planet in self.planets.iter_mut().filter(|p| p.apply_forces) {
+                planet.vel -= vel;
+            }
+        }
+    }
+
+    /// Moves the barycenter to the origin and removes the total momentum when possible.
+    pub fn to_barycentric(&mut self) {
+        if let Some(center) = self.barycenter(1.) {
+            for planet in self.planets.iter_mut() {
+                planet.pos -= center;
+                planet.prev_pos -= center;
+                planet.delta_pos -= center;
+            }
+        }
+        self.zero_momentum();
+    }
+
+    /// Flips every velocity so the simulation runs back towards its initial conditions.
+    pub fn reverse(&mut self) {
+        for planet in self.planets.iter_mut().filter(|p| p.apply_forces) {
+            planet.vel = -planet.vel;
+            planet.exact.1 = -planet.exact.1;
+        }
+        if let Some(shadow) = self.shadow.as_mut() {
+            for planet in shadow.planets.iter_mut().filter(|p| p.apply_forces) {
+                planet.vel = -planet.vel;
+                planet.exact.1 = -planet.exact.1;
+            }
+        }
+        self.reversed = !self.reversed;
+        self.round_trip_error = None;
+    }
+
+    /// Advances the simulation by one tick. Returns false once a reversed run has arrived
+    /// back at its initial conditions, the velocities are then flipped back and the round
+    /// trip error is measured.
+    pub fn step(&mut self) -> bool {
+        for planet in self.planets.iter_mut() {
+            planet.prev_pos = planet.pos;
+        }
+
+        if self.reversed && self.time <= 0. {
+            self.reverse();
+            self.time = 0.;
+            self.round_trip_error = Some(self.planets.iter().zip(&self.initial).map(|(p, i)| p.pos.distance(i.pos)).fold(0., f32::max));
+            return false;
+        }
+
+        let regularization = self.regularize.then_some(self.regularization_radius);
+        advance(&mut self.planets, self.integrator, self.softening, regularization);
+        if let Some(shadow) = self.shadow.as_mut() {
+            advance(&mut shadow.planets, self.integrator, self.softening, regularization);
+            shadow.stepped(&self.planets);
+        }
+
+        self.time += if self.reversed { -1. } else { 1. };
+        if self.history.tick() {
+            self.sample_history();
+        }
+        if self.detect_events {
+            self.events.detect(self.time, &self.planets, self.integrator, self.softening);
+        }
+        if self.record_section {
+            self.section.update(&self.planets);
+        }
+        self.handle_escapers();
+        true
+    }
+
+    /// Applies the escape policy to moving bodies that are unbound from the system and
+    /// farther than `escape_radius` from the barycenter of the bodies still taking part.
+    fn handle_escapers(&mut self) {
+        if self.escape_policy == EscapePolicy::Ignore {
+            return;
+        }
+
+        let active = self.planets.iter().filter(|p| !p.frozen);
+        let mass = active.clone().map(|p| p.m).sum::<f32>();
+        if mass <= 0. {
+            return;
+        }
+        let center = active.map(|p| p.pos * p.m).sum::<Vec2>() / mass;
+
+        let mut changed = false;
+        let mut i = 0;
+        while i < self.planets.len() {
+            let planet = &self.planets[i];
+            if planet.apply_forces && !planet.escaped && planet.pos.distance(center) > self.escape_radius && !orbit::bound_to_system(&self.planets, i, self.integrator, self.softening) {
+                self.events.escaped(self.time, planet.id, self.escape_policy);
+                match self.escape_policy {
+                    EscapePolicy::Ignore | EscapePolicy::Flag => self.planets[i].escaped = true,
+                    EscapePolicy::Freeze => {
+                        self.planets[i].escaped = true;
+                        self.planets[i].frozen = true;
+                        changed = true;
+                    }
+                    EscapePolicy::Remove => {
+                        let id = self.planets.remove(i).id;
+                        self.initial.retain(|p| p.id != id);
+                        self.removed += 1;
+                        changed = true;
+                        continue;
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        // The system lost the energy of the bodies that left
+        if changed {
+            self.rebase_energy();
+            self.restart_shadow();
+        }
+    }
+}
+
+/// Steps the bodies that aren't frozen by one tick.
+fn advance(planets: &mut [Mover], integrator: Integrator, softening: f32, regularization: Option<f32>) {
+    if planets.iter().any(|p| p.frozen) {
+        let mut active = planets.iter().filter(|p| !p.frozen).copied().collect::<Vec<_>>();
+        integrator.step(&mut active, softening, regularization);
+        for (planet, stepped) in planets.iter_mut().filter(|p| !p.frozen).zip(active) {
+            *planet = stepped;
+        }
+    } else {
+        integrator.step(planets, softening, regularization);
+    }
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,119 @@
+use std::{collections::HashMap, f64::consts::{PI, TAU}};
+
+use notan::{math::DVec2, prelude::Color};
+
+use crate::{integrator::Integrator, scenario::{Scenario, Localized, View, Body}, units::{Units, UnitSystem}, vec_math, G};
+
+pub const FILE: &str = "solar-system.json";
+
+const SCALE: f64 = 100.;
+const STEP: f64 = 0.5;
+
+struct Planet {
+    a: f64,
+    e: f64,
+    mean_longitude: f64,
+    perihelion: f64,
+    mass: f64,
+    radius: f32,
+    color: u32
+}
+
+/// Mean elements at the J2000 epoch, JD 2451545.0, from Standish's approximate positions
+/// of the major planets (JPL, valid 1800 to 2050). The Earth is the Earth-Moon barycentre.
+/// Inclinations are left out, the orbits are laid flat into the ecliptic.
+const PLANETS: [Planet; 8] = [
+    Planet { a: 0.38709927, e: 0.20563593, mean_longitude: 252.25032350, perihelion: 77.45779628, mass: 1.6601141e-7, radius: 3., color: 0xA8A29EFF },
+    Planet { a: 0.72333566, e: 0.00677672, mean_longitude: 181.97909950, perihelion: 131.60246718, mass: 2.4478383e-6, radius: 4., color: 0xE8C872FF },
+    Planet { a: 1.00000261, e: 0.01671123, mean_longitude: 100.46457166, perihelion: 102.93768193, mass: 3.0404326e-6, radius: 4., color: 0x4C8DDBFF },
+    Planet { a: 1.52371034, e: 0.09339410, mean_longitude: -4.55343205, perihelion: -23.94362959, mass: 3.2271560e-7, radius: 3.5, color: 0xD0623BFF },
+    Planet { a: 5.20288700, e: 0.04838624, mean_longitude: 34.39644051, perihelion: 14.72847983, mass: 9.5479194e-4, radius: 9., color: 0xD9A066FF },
+    Planet { a: 9.53667594, e: 0.05386179, mean_longitude: 49.95424423, perihelion: 92.59887831, mass: 2.8588598e-4, radius: 8., color: 0xE3CF8CFF },
+    Planet { a: 19.18916464, e: 0.04725744, mean_longitude: 313.23810451, perihelion: 170.95427630, mass: 4.3662440e-5, radius: 6., color: 0x9FD8E0FF },
+    Planet { a: 30.06992276, e: 0.00859048, mean_longitude: -55.12002969, perihelion: 44.96476227, mass: 5.1513890e-5, radius: 6., color: 0x4F6FD9FF }
+];
+
+pub fn scenario() -> Scenario {
+    let units = Units { system: UnitSystem::Astronomical, scale: SCALE, step: STEP };
+    let g = units.system.g();
+
+    let mut states = vec![(DVec2::ZERO, DVec2::ZERO, 1.)];
+    for planet in &PLANETS {
+        let perihelion = planet.perihelion.to_radians();
+        let mean_anomaly = wrap(planet.mean_longitude.to_radians() - perihelion);
+        let (pos, vel) = state_vectors(planet.a, planet.e, perihelion, eccentric_anomaly(mean_anomaly, planet.e), g * (1. + planet.mass));
+        states.push((pos, vel, planet.mass));
+    }
+
+    let total = states.iter().map(|s| s.2).sum::<f64>();
+    let center = states.iter().map(|s| s.0 * s.2).sum::<DVec2>() / total;
+    let drift = states.iter().map(|s| s.1 * s.2).sum::<DVec2>() / total;
+
+    let looks = std::iter::once((12., 0xFFD54AFF)).chain(PLANETS.iter().map(|p| (p.radius, p.color)));
+    let bodies = states.iter().zip(looks).map(|(&(pos, vel, mass), (radius, color))| {
+        // Screen y points down, flipping it shows the ecliptic from the north
+        let (pos, vel) = (pos - center, vel - drift);
+        Body {
+            x: pos.x as f32,
+            y: -pos.y as f32,
+            mass: mass as f32,
+            vx: vel.x as f32,
+            vy: -vel.y as f32,
+            fixed: false,
+            radius: Some(radius),
+            color: Some(Color::from_hex(color))
+        }
+    }).collect();
+
+    Scenario {
+        name: Some(by_language("Solar System", "Saulės sistema")),
+        description: Some(by_language(
+            "The Sun and the eight planets where they were on 1 January 2000, placed from their orbital elements in the plane of the ecliptic.",
+            "Saulė ir aštuonios planetos 2000 m. sausio 1 d. padėtyse, apskaičiuotose iš orbitų elementų, ekliptikos plokštumoje."
+        )),
+        author: None,
+        tags: vec!["orbits".to_string(), "real".to_string()],
+        sim_speed: Some(200.),
+        seed: None,
+        g: G,
+        units: Some(units),
+        softening: Some(0.001),
+        integrator: Some(Integrator::Hermite),
+        camera: View { x: 0., y: 0., zoom: 0.5 },
+        bodies
+    }
+}
+
+fn by_language(en: &str, lt: &str) -> Localized {
+    Localized::ByLanguage(HashMap::from([("en".to_string(), en.to_string()), ("lt".to_string(), lt.to_string())]))
+}
+
+fn wrap(angle: f64) -> f64 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+fn eccentric_anomaly(mean_anomaly: f64, e: f64) -> f64 {
+    let mut eccentric = if e < 0.8 { mean_anomaly } else { PI.copysign(mean_anomaly) };
+    for _ in 0..20 {
+        let (sin, cos) = vec_math::sin_cos(eccentric);
+        let delta = (eccentric - e * sin - mean_anomaly) / (1. - e * cos);
+        eccentric -= delta;
+        if delta.abs() < 1e-14 {
+            break;
+        }
+    }
+    eccentric
+}
+
+/// State relative to the Sun, found without `atan2`, which is not rounded the same on every platform.
+fn state_vectors(a: f64, e: f64, perihelion: f64, eccentric: f64, mu: f64) -> (DVec2, DVec2) {
+    let (sin, cos) = vec_math::sin_cos(eccentric);
+    let minor = (1. - e * e).sqrt();
+    let r = a * (1. - e * cos);
+
+    let pos = DVec2::new(cos - e, minor * sin) * a;
+    let vel = DVec2::new(-sin, minor * cos) * ((mu * a).sqrt() / r);
+    let (sin_w, cos_w) = vec_math::sin_cos(perihelion);
+    let rotation = DVec2::new(cos_w, sin_w);
+    (rotation.rotate(pos), rotation.rotate(vel))
+}
\ No newline at end of file
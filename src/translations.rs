@@ -13,7 +13,7 @@ impl Translations {
         translations.insert("ltchooselang".to_string(), "Pasirinkite kalbą: ".to_string());
         translations.insert("ltpattern".to_string(), "išdėstymas".to_string());
         translations.insert("ltchoose".to_string(), "Pasirinkite pradinį išsidėstymą: ".to_string());
-        translations.insert("ltsimspeed".to_string(), "Simuliacijos greitis".to_string());
+        translations.insert("ltsimspeed".to_string(), "Simuliacijos greitis (laiko vnt./s)".to_string());
         translations.insert("ltshowtrail".to_string(), "Rodyti trajektoriją".to_string());
         translations.insert("ltshowbodies".to_string(), "Rodyti kūnus".to_string());
         translations.insert("ltpause".to_string(), "Sustabdyti".to_string());
@@ -38,13 +38,153 @@ impl Translations {
         translations.insert("lteditor".to_string(), "Redagavimas".to_string());
         translations.insert("lttrack".to_string(), "Sekti".to_string());
         translations.insert("ltuntrack".to_string(), "Nustoti sekima".to_string());
+        translations.insert("ltintegrator".to_string(), "Integravimo metodas: ".to_string());
+        translations.insert("lteuler".to_string(), "Eulerio".to_string());
+        translations.insert("lthermite".to_string(), "Hermito (blokiniai žingsniai)".to_string());
+        translations.insert("ltsoftening".to_string(), "Švelninimas: ".to_string());
+        translations.insert("ltsteplevel".to_string(), "Žingsnio lygis".to_string());
+        translations.insert("ltregularize".to_string(), "Reguliarizuoti artimas poras, spindulys:".to_string());
+        translations.insert("ltleapfrog".to_string(), "Šuolinis (apgręžiamas)".to_string());
+        translations.insert("ltreverse".to_string(), "Apgręžti laiką".to_string());
+        translations.insert("ltreverseleapfrog".to_string(), "Laiką galima apgręžti tik su Leapfrog integratoriumi, kuris yra simetriškas laike".to_string());
+        translations.insert("ltforward".to_string(), "Leisti pirmyn".to_string());
+        translations.insert("lttime".to_string(), "Laikas".to_string());
+        translations.insert("ltdays".to_string(), "d.".to_string());
+        translations.insert("ltyears".to_string(), "m.".to_string());
+        translations.insert("ltroundtrip".to_string(), "Grįžimo paklaida".to_string());
+        translations.insert("ltseed".to_string(), "Sėkla: ".to_string());
+        translations.insert("ltnewseed".to_string(), "Nauja sėkla".to_string());
+        translations.insert("ltseedhint".to_string(), "Sėkla pritaikoma paleidus iš naujo".to_string());
+        translations.insert("ltconservation".to_string(), "Tvermės dėsniai".to_string());
+        translations.insert("ltkinetic".to_string(), "Kinetinė energija".to_string());
+        translations.insert("ltpotential".to_string(), "Potencinė energija".to_string());
+        translations.insert("ltenergy".to_string(), "Pilnutinė energija".to_string());
+        translations.insert("ltdrift".to_string(), "Energijos dreifas".to_string());
+        translations.insert("ltmomentum".to_string(), "Judesio kiekis".to_string());
+        translations.insert("ltangmomentum".to_string(), "Judesio kiekio momentas".to_string());
+        translations.insert("ltlogto".to_string(), "Rašyti į".to_string());
+        translations.insert("ltshowplots".to_string(), "Rodyti grafikus".to_string());
+        translations.insert("ltplots".to_string(), "Grafikai".to_string());
+        translations.insert("ltaddplot".to_string(), "Pridėti".to_string());
+        translations.insert("ltplotinterval".to_string(), "Matavimo intervalas: ".to_string());
+        translations.insert("ltvirial".to_string(), "Virialo santykis".to_string());
+        translations.insert("ltdistance".to_string(), "Atstumas".to_string());
+        translations.insert("ltspeed".to_string(), "Greitis".to_string());
+        translations.insert("ltparent".to_string(), "Centrinis kūnas:".to_string());
+        translations.insert("ltauto".to_string(), "automatiškai".to_string());
+        translations.insert("ltapsides".to_string(), "Periapsis / apoapsis".to_string());
+        translations.insert("ltperiapsis".to_string(), "Periapsis".to_string());
+        translations.insert("ltperiod".to_string(), "Periodas".to_string());
+        translations.insert("ltbound".to_string(), "surišta orbita".to_string());
+        translations.insert("ltunbound".to_string(), "Nesurišta orbita".to_string());
+        translations.insert("ltatmouse".to_string(), "Ties pele".to_string());
+        translations.insert("ltonorbit".to_string(), "Orbitoje".to_string());
+        translations.insert("ltnoparent".to_string(), "Pirma pridėkite centrinį kūną".to_string());
+        translations.insert("lttrueanomaly".to_string(), "Tikroji anomalija: ".to_string());
+        translations.insert("ltplacebody".to_string(), "Pridėti kūną".to_string());
+        translations.insert("lteulerspeedlimit".to_string(), "Per greitas Eulerio dėsniui, greitis bus apribotas iki 10".to_string());
+        translations.insert("ltshowbarycenter".to_string(), "Rodyti masių centrą".to_string());
+        translations.insert("lttrackbarycenter".to_string(), "Sekti masių centrą".to_string());
+        translations.insert("ltbarycentricload".to_string(), "Įkelti masių centro sistemoje".to_string());
+        translations.insert("ltzeromomentum".to_string(), "Panaikinti judesio kiekį".to_string());
+        translations.insert("ltfixedanchor".to_string(), "Nejudantys kūnai pririša atskaitos sistemą".to_string());
+        translations.insert("ltshowevents".to_string(), "Rodyti įvykius".to_string());
+        translations.insert("ltevents".to_string(), "Įvykiai".to_string());
+        translations.insert("ltdetectevents".to_string(), "Aptikti įvykius".to_string());
+        translations.insert("ltapproachdist".to_string(), "Priartėjimo atstumas: ".to_string());
+        translations.insert("ltexport".to_string(), "Eksportuoti į".to_string());
+        translations.insert("ltsaved".to_string(), "Išsaugota į".to_string());
+        translations.insert("ltbuiltinname".to_string(), "šis vardas užimtas įtaisyto scenarijaus, pasirinkite kitą".to_string());
+        translations.insert("ltcloseapproach".to_string(), "Priartėjimas".to_string());
+        translations.insert("ltescapers".to_string(), "Pabėgę kūnai".to_string());
+        translations.insert("ltescaperadius".to_string(), "Pabėgimo spindulys: ".to_string());
+        translations.insert("ltremoved".to_string(), "Pašalinta".to_string());
+        translations.insert("ltignore".to_string(), "Nieko nedaryti".to_string());
+        translations.insert("ltflag".to_string(), "Pažymėti".to_string());
+        translations.insert("ltfreeze".to_string(), "Užšaldyti".to_string());
+        translations.insert("ltremovebody".to_string(), "Pašalinti".to_string());
+        translations.insert("ltescaped".to_string(), "pabėgo".to_string());
+        translations.insert("ltfrozen".to_string(), "užšaldytas".to_string());
+        translations.insert("ltchaos".to_string(), "Chaosas".to_string());
+        translations.insert("ltshadow".to_string(), "Sekti šešėlinę trajektoriją".to_string());
+        translations.insert("ltlyapunov".to_string(), "Liapunovo eksponentė".to_string());
+        translations.insert("ltshadowhint".to_string(), "Nukrypimo augimą galima stebėti grafikuose".to_string());
+        translations.insert("ltdivergence".to_string(), "Nukrypimas".to_string());
+        translations.insert("ltshowsection".to_string(), "Rodyti Puankarė pjūvį".to_string());
+        translations.insert("ltsection".to_string(), "Puankarė pjūvis".to_string());
+        translations.insert("ltrecordsection".to_string(), "Įrašyti".to_string());
+        translations.insert("ltcrossingbody".to_string(), "Kūnas, kertantis ".to_string());
+        translations.insert("ltrecordedbody".to_string(), "Įrašomas kūnas: ".to_string());
+        translations.insert("ltrelativeto".to_string(), "atžvilgiu".to_string());
+        translations.insert("ltworldorigin".to_string(), "koordinačių pradžios".to_string());
+        translations.insert("ltxaxis".to_string(), "x ašį".to_string());
+        translations.insert("ltyaxis".to_string(), "y ašį".to_string());
+        translations.insert("ltincreasing".to_string(), "didėjant".to_string());
+        translations.insert("ltdecreasing".to_string(), "mažėjant".to_string());
+        translations.insert("ltboth".to_string(), "abiem kryptimis".to_string());
+        translations.insert("ltpoints".to_string(), "Taškai".to_string());
+        translations.insert("ltclearpoints".to_string(), "Išvalyti".to_string());
+        translations.insert("ltshowpotential".to_string(), "Rodyti potencialą".to_string());
+        translations.insert("ltshowcontours".to_string(), "Ekvipotencialės".to_string());
+        translations.insert("ltshowvelocity".to_string(), "Greičio vektoriai".to_string());
+        translations.insert("ltshowacceleration".to_string(), "Pagreičio vektoriai".to_string());
+        translations.insert("ltshowcontributions".to_string(), "Kiekvieno kūno traukos dalis pasirinktam kūnui".to_string());
+        translations.insert("ltshowspacetime".to_string(), "Erdvėlaikio tinklelis".to_string());
+        translations.insert("ltpreviewticks".to_string(), "Trajektorijos peržiūra (žingsniai): ".to_string());
+        translations.insert("ltshowforecast".to_string(), "Numatoma trajektorija".to_string());
+        translations.insert("ltticks".to_string(), "žingsniai".to_string());
+        translations.insert("ltsimunits".to_string(), "Reikšmės simuliacijos vienetais: pikseliai, žingsniai ir masės, kai G = 10".to_string());
+        translations.insert("lttopic".to_string(), "Tema".to_string());
+        translations.insert("ltalltopics".to_string(), "Visos temos".to_string());
+        translations.insert("ltauthor".to_string(), "Autorius".to_string());
+        translations.insert("ltsavescenario".to_string(), "Išsaugoti scenarijų".to_string());
+        translations.insert("ltloadscenario".to_string(), "Įkelti scenarijų".to_string());
+        translations.insert("ltloaded".to_string(), "Įkelta".to_string());
+        translations.insert("ltshowgenerators".to_string(), "Generatoriai".to_string());
+        translations.insert("ltgenerators".to_string(), "Kūnų generatoriai".to_string());
+        translations.insert("ltgenerate".to_string(), "Generuoti".to_string());
+        translations.insert("ltplummer".to_string(), "Plummerio sfera".to_string());
+        translations.insert("ltking".to_string(), "Kingo profilis".to_string());
+        translations.insert("ltkingvelocities".to_string(), "Padėtys pagal Kingo (1962) tankio profilį, greičiai atsitiktiniai ir atitinka tik virialinį santykį".to_string());
+        translations.insert("ltdisk".to_string(), "Besisukantis diskas".to_string());
+        translations.insert("ltring".to_string(), "Keplerio žiedas".to_string());
+        translations.insert("ltclusters".to_string(), "Atsitiktiniai spiečiai".to_string());
+        translations.insert("ltlattice".to_string(), "Gardelė".to_string());
+        translations.insert("ltgalaxies".to_string(), "Galaktikų susidūrimas".to_string());
+        translations.insert("ltbodycount".to_string(), "Kūnų skaičius: ".to_string());
+        translations.insert("lttotalmass".to_string(), "Bendra masė: ".to_string());
+        translations.insert("ltscaleradius".to_string(), "Mastelio spindulys: ".to_string());
+        translations.insert("ltcoreradius".to_string(), "Branduolio spindulys: ".to_string());
+        translations.insert("lttidalradius".to_string(), "Potvynio spindulys: ".to_string());
+        translations.insert("ltinnerradius".to_string(), "Vidinis spindulys: ".to_string());
+        translations.insert("ltouterradius".to_string(), "Išorinis spindulys: ".to_string());
+        translations.insert("ltringradius".to_string(), "Žiedo spindulys: ".to_string());
+        translations.insert("ltclustercount".to_string(), "Spiečių skaičius: ".to_string());
+        translations.insert("ltclusterradius".to_string(), "Spiečiaus spindulys: ".to_string());
+        translations.insert("ltregionradius".to_string(), "Srities spindulys: ".to_string());
+        translations.insert("ltcolumns".to_string(), "Stulpeliai: ".to_string());
+        translations.insert("ltrows".to_string(), "Eilutės: ".to_string());
+        translations.insert("ltspacing".to_string(), "Tarpas: ".to_string());
+        translations.insert("ltjitter".to_string(), "Atsitiktinis poslinkis: ".to_string());
+        translations.insert("lttargetvirial".to_string(), "Virialo santykis: ".to_string());
+        translations.insert("ltcentralmass".to_string(), "Centrinė masė: ".to_string());
+        translations.insert("ltfixed".to_string(), "Nejudanti".to_string());
+        translations.insert("ltkeepexisting".to_string(), "Palikti esamus kūnus".to_string());
+        translations.insert("ltcoremass".to_string(), "Branduolio masė: ".to_string());
+        translations.insert("ltmassratio".to_string(), "Masių santykis: ".to_string());
+        translations.insert("ltseparation".to_string(), "Atstumas: ".to_string());
+        translations.insert("ltimpactparameter".to_string(), "Taikymo nuotolis: ".to_string());
+        translations.insert("ltapproachspeed".to_string(), "Artėjimo greitis: ".to_string());
+        translations.insert("ltfirstretrograde".to_string(), "Pirmas diskas priešinga kryptimi".to_string());
+        translations.insert("ltsecondretrograde".to_string(), "Antras diskas priešinga kryptimi".to_string());
+        translations.insert("ltgalaxiesleapfrog".to_string(), "Eulerio dėsnis neturi potvynio jėgų, bus naudojamas Leapfrog".to_string());
 
 
         translations.insert("ensettings".to_string(), "Settings".to_string());
         translations.insert("enchooselang".to_string(), "Choose language: ".to_string());
         translations.insert("enpattern".to_string(), "pattern".to_string());
         translations.insert("enchoose".to_string(), "Choose pattern: ".to_string());
-        translations.insert("ensimspeed".to_string(), "Simulation speed".to_string());
+        translations.insert("ensimspeed".to_string(), "Simulation speed (time units/s)".to_string());
         translations.insert("enshowtrail".to_string(), "Show trails".to_string());
         translations.insert("enshowbodies".to_string(), "Show bodies".to_string());
         translations.insert("enpause".to_string(), "Pause".to_string());
@@ -69,6 +209,146 @@ impl Translations {
         translations.insert("eneditor".to_string(), "Editor".to_string());
         translations.insert("entrack".to_string(), "Track".to_string());
         translations.insert("enuntrack".to_string(), "Stop tracking".to_string());
+        translations.insert("enintegrator".to_string(), "Integrator: ".to_string());
+        translations.insert("eneuler".to_string(), "Euler".to_string());
+        translations.insert("enhermite".to_string(), "Hermite (block steps)".to_string());
+        translations.insert("ensoftening".to_string(), "Softening: ".to_string());
+        translations.insert("ensteplevel".to_string(), "Step level".to_string());
+        translations.insert("enregularize".to_string(), "Regularize close pairs, radius:".to_string());
+        translations.insert("enleapfrog".to_string(), "Leapfrog (reversible)".to_string());
+        translations.insert("enreverse".to_string(), "Reverse time".to_string());
+        translations.insert("enreverseleapfrog".to_string(), "Time can only be reversed with the Leapfrog integrator, which is time symmetric".to_string());
+        translations.insert("enforward".to_string(), "Run forward".to_string());
+        translations.insert("entime".to_string(), "Time".to_string());
+        translations.insert("endays".to_string(), "days".to_string());
+        translations.insert("enyears".to_string(), "years".to_string());
+        translations.insert("enroundtrip".to_string(), "Round trip error".to_string());
+        translations.insert("enseed".to_string(), "Seed: ".to_string());
+        translations.insert("ennewseed".to_string(), "New seed".to_string());
+        translations.insert("enseedhint".to_string(), "The seed is applied on restart".to_string());
+        translations.insert("enconservation".to_string(), "Conservation".to_string());
+        translations.insert("enkinetic".to_string(), "Kinetic energy".to_string());
+        translations.insert("enpotential".to_string(), "Potential energy".to_string());
+        translations.insert("enenergy".to_string(), "Total energy".to_string());
+        translations.insert("endrift".to_string(), "Energy drift".to_string());
+        translations.insert("enmomentum".to_string(), "Momentum".to_string());
+        translations.insert("enangmomentum".to_string(), "Angular momentum".to_string());
+        translations.insert("enlogto".to_string(), "Log to".to_string());
+        translations.insert("enshowplots".to_string(), "Show plots".to_string());
+        translations.insert("enplots".to_string(), "Plots".to_string());
+        translations.insert("enaddplot".to_string(), "Add".to_string());
+        translations.insert("enplotinterval".to_string(), "Sample interval: ".to_string());
+        translations.insert("envirial".to_string(), "Virial ratio".to_string());
+        translations.insert("endistance".to_string(), "Distance".to_string());
+        translations.insert("enspeed".to_string(), "Speed".to_string());
+        translations.insert("enparent".to_string(), "Parent:".to_string());
+        translations.insert("enauto".to_string(), "auto".to_string());
+        translations.insert("enapsides".to_string(), "Periapsis / apoapsis".to_string());
+        translations.insert("enperiapsis".to_string(), "Periapsis".to_string());
+        translations.insert("enperiod".to_string(), "Period".to_string());
+        translations.insert("enbound".to_string(), "bound".to_string());
+        translations.insert("enunbound".to_string(), "Unbound".to_string());
+        translations.insert("enatmouse".to_string(), "At mouse".to_string());
+        translations.insert("enonorbit".to_string(), "On orbit".to_string());
+        translations.insert("ennoparent".to_string(), "Add a parent body first".to_string());
+        translations.insert("entrueanomaly".to_string(), "True anomaly: ".to_string());
+        translations.insert("enplacebody".to_string(), "Add body".to_string());
+        translations.insert("eneulerspeedlimit".to_string(), "Too fast for the Euler law, the speed will be limited to 10".to_string());
+        translations.insert("enshowbarycenter".to_string(), "Show barycenter".to_string());
+        translations.insert("entrackbarycenter".to_string(), "Track barycenter".to_string());
+        translations.insert("enbarycentricload".to_string(), "Load in barycentric frame".to_string());
+        translations.insert("enzeromomentum".to_string(), "Zero total momentum".to_string());
+        translations.insert("enfixedanchor".to_string(), "Fixed bodies anchor the frame".to_string());
+        translations.insert("enshowevents".to_string(), "Show events".to_string());
+        translations.insert("enevents".to_string(), "Events".to_string());
+        translations.insert("endetectevents".to_string(), "Detect events".to_string());
+        translations.insert("enapproachdist".to_string(), "Approach distance: ".to_string());
+        translations.insert("enexport".to_string(), "Export to".to_string());
+        translations.insert("ensaved".to_string(), "Saved to".to_string());
+        translations.insert("enbuiltinname".to_string(), "this name belongs to a built-in scenario, pick another".to_string());
+        translations.insert("encloseapproach".to_string(), "Close approach".to_string());
+        translations.insert("enescapers".to_string(), "Escapers".to_string());
+        translations.insert("enescaperadius".to_string(), "Escape radius: ".to_string());
+        translations.insert("enremoved".to_string(), "Removed".to_string());
+        translations.insert("enignore".to_string(), "Ignore".to_string());
+        translations.insert("enflag".to_string(), "Flag".to_string());
+        translations.insert("enfreeze".to_string(), "Freeze".to_string());
+        translations.insert("enremovebody".to_string(), "Remove".to_string());
+        translations.insert("enescaped".to_string(), "escaped".to_string());
+        translations.insert("enfrozen".to_string(), "frozen".to_string());
+        translations.insert("enchaos".to_string(), "Chaos".to_string());
+        translations.insert("enshadow".to_string(), "Follow a shadow trajectory".to_string());
+        translations.insert("enlyapunov".to_string(), "Lyapunov exponent".to_string());
+        translations.insert("enshadowhint".to_string(), "The divergence growth can be plotted in the plots window".to_string());
+        translations.insert("endivergence".to_string(), "Divergence".to_string());
+        translations.insert("enshowsection".to_string(), "Show Poincaré section".to_string());
+        translations.insert("ensection".to_string(), "Poincaré section".to_string());
+        translations.insert("enrecordsection".to_string(), "Record".to_string());
+        translations.insert("encrossingbody".to_string(), "Body crossing ".to_string());
+        translations.insert("enrecordedbody".to_string(), "Recorded body: ".to_string());
+        translations.insert("enrelativeto".to_string(), "relative to".to_string());
+        translations.insert("enworldorigin".to_string(), "world origin".to_string());
+        translations.insert("enxaxis".to_string(), "the x axis".to_string());
+        translations.insert("enyaxis".to_string(), "the y axis".to_string());
+        translations.insert("enincreasing".to_string(), "increasing".to_string());
+        translations.insert("endecreasing".to_string(), "decreasing".to_string());
+        translations.insert("enboth".to_string(), "both ways".to_string());
+        translations.insert("enpoints".to_string(), "Points".to_string());
+        translations.insert("enclearpoints".to_string(), "Clear".to_string());
+        translations.insert("enshowpotential".to_string(), "Show potential".to_string());
+        translations.insert("enshowcontours".to_string(), "Equipotentials".to_string());
+        translations.insert("enshowvelocity".to_string(), "Velocity vectors".to_string());
+        translations.insert("enshowacceleration".to_string(), "Acceleration vectors".to_string());
+        translations.insert("enshowcontributions".to_string(), "Pairwise pulls on the selected body".to_string());
+        translations.insert("enshowspacetime".to_string(), "Spacetime grid".to_string());
+        translations.insert("enpreviewticks".to_string(), "Path preview (ticks): ".to_string());
+        translations.insert("enshowforecast".to_string(), "Predicted path".to_string());
+        translations.insert("enticks".to_string(), "ticks".to_string());
+        translations.insert("ensimunits".to_string(), "Values in simulation units: pixels, ticks and masses for G = 10".to_string());
+        translations.insert("entopic".to_string(), "Topic".to_string());
+        translations.insert("enalltopics".to_string(), "All topics".to_string());
+        translations.insert("enauthor".to_string(), "Author".to_string());
+        translations.insert("ensavescenario".to_string(), "Save as scenario".to_string());
+        translations.insert("enloadscenario".to_string(), "Load scenario".to_string());
+        translations.insert("enloaded".to_string(), "Loaded".to_string());
+        translations.insert("enshowgenerators".to_string(), "Generators".to_string());
+        translations.insert("engenerators".to_string(), "Body generators".to_string());
+        translations.insert("engenerate".to_string(), "Generate".to_string());
+        translations.insert("enplummer".to_string(), "Plummer sphere".to_string());
+        translations.insert("enking".to_string(), "King profile".to_string());
+        translations.insert("enkingvelocities".to_string(), "Positions follow King's (1962) density profile, the velocities are random and only match the virial ratio".to_string());
+        translations.insert("endisk".to_string(), "Rotating disk".to_string());
+        translations.insert("enring".to_string(), "Keplerian ring".to_string());
+        translations.insert("enclusters".to_string(), "Random clusters".to_string());
+        translations.insert("enlattice".to_string(), "Lattice".to_string());
+        translations.insert("engalaxies".to_string(), "Colliding galaxies".to_string());
+        translations.insert("enbodycount".to_string(), "Bodies: ".to_string());
+        translations.insert("entotalmass".to_string(), "Total mass: ".to_string());
+        translations.insert("enscaleradius".to_string(), "Scale radius: ".to_string());
+        translations.insert("encoreradius".to_string(), "Core radius: ".to_string());
+        translations.insert("entidalradius".to_string(), "Tidal radius: ".to_string());
+        translations.insert("eninnerradius".to_string(), "Inner radius: ".to_string());
+        translations.insert("enouterradius".to_string(), "Outer radius: ".to_string());
+        translations.insert("enringradius".to_string(), "Ring radius: ".to_string());
+        translations.insert("enclustercount".to_string(), "Clusters: ".to_string());
+        translations.insert("enclusterradius".to_string(), "Cluster radius: ".to_string());
+        translations.insert("enregionradius".to_string(), "Region radius: ".to_string());
+        translations.insert("encolumns".to_string(), "Columns: ".to_string());
+        translations.insert("enrows".to_string(), "Rows: ".to_string());
+        translations.insert("enspacing".to_string(), "Spacing: ".to_string());
+        translations.insert("enjitter".to_string(), "Jitter: ".to_string());
+        translations.insert("entargetvirial".to_string(), "Virial ratio: ".to_string());
+        translations.insert("encentralmass".to_string(), "Central mass: ".to_string());
+        translations.insert("enfixed".to_string(), "Fixed".to_string());
+        translations.insert("enkeepexisting".to_string(), "Keep the current bodies".to_string());
+        translations.insert("encoremass".to_string(), "Core mass: ".to_string());
+        translations.insert("enmassratio".to_string(), "Mass ratio: ".to_string());
+        translations.insert("enseparation".to_string(), "Separation: ".to_string());
+        translations.insert("enimpactparameter".to_string(), "Impact parameter: ".to_string());
+        translations.insert("enapproachspeed".to_string(), "Approach speed: ".to_string());
+        translations.insert("enfirstretrograde".to_string(), "First disk retrograde".to_string());
+        translations.insert("ensecondretrograde".to_string(), "Second disk retrograde".to_string());
+        translations.insert("engalaxiesleapfrog".to_string(), "The Euler law has no tides, Leapfrog will be used".to_string());
 
         Self {
             translations,
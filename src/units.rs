@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use crate::G;
+
+const DAY: f64 = 86400.;
+const YEAR: f64 = 365.25;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    Astronomical,
+    Si
+}
+
+impl UnitSystem {
+    pub fn g(&self) -> f64 {
+        match self {
+            // The square of the Gaussian gravitational constant
+            UnitSystem::Astronomical => 0.01720209895 * 0.01720209895,
+            UnitSystem::Si => 6.6743e-11
+        }
+    }
+
+    fn day_fraction(&self) -> f64 {
+        match self {
+            UnitSystem::Astronomical => 1.,
+            UnitSystem::Si => 1. / DAY
+        }
+    }
+}
+
+/// How physical units map onto the simulation, where lengths are pixels, a tick is the
+/// time unit and masses pull with `G`. Masses follow from the other two, so that bodies
+/// attract each other as hard as they would in the physical units.
+#[derive(Deserialize, Serialize, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct Units {
+    pub system: UnitSystem,
+    pub scale: f64,
+    pub step: f64
+}
+
+impl Units {
+    pub fn length(&self, x: f64) -> f32 {
+        (x * self.scale) as f32
+    }
+
+    pub fn velocity(&self, v: f64) -> f32 {
+        (v * self.scale * self.step) as f32
+    }
+
+    pub fn mass(&self, m: f64) -> f32 {
+        (m * self.system.g() * self.scale.powi(3) * self.step * self.step / G as f64) as f32
+    }
+
+    pub fn from_length(&self, x: f32) -> f64 {
+        x as f64 / self.scale
+    }
+
+    pub fn from_velocity(&self, v: f32) -> f64 {
+        v as f64 / (self.scale * self.step)
+    }
+
+    pub fn from_mass(&self, m: f32) -> f64 {
+        m as f64 * G as f64 / (self.system.g() * self.scale.powi(3) * self.step * self.step)
+    }
+
+    pub fn days(&self, ticks: f64) -> f64 {
+        ticks * self.step * self.system.day_fraction()
+    }
+
+    pub fn elapsed(&self, ticks: f64) -> (f64, &'static str) {
+        let days = self.days(ticks);
+        if days.abs() < YEAR {
+            (days, "days")
+        } else {
+            (days / YEAR, "years")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions_round_trip() {
+        let units = Units { system: UnitSystem::Astronomical, scale: 100., step: 0.5 };
+        for x in [3.0404326e-6, 1., 1e3] {
+            assert!((units.from_mass(units.mass(x)) / x - 1.).abs() < 1e-6);
+            assert!((units.from_length(units.length(x)) / x - 1.).abs() < 1e-6);
+            assert!((units.from_velocity(units.velocity(x)) / x - 1.).abs() < 1e-6);
+        }
+    }
+}
\ No newline at end of file
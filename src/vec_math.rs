@@ -1,4 +1,4 @@
-use std::ops::MulAssign;
+use std::{ops::MulAssign, f64::consts::FRAC_PI_2};
 
 use notan::math::Vec2;
 
@@ -23,4 +23,73 @@ pub fn limit(v: &mut Vec2, limit: f32) {
     } else if v.y < -limit{
         v.y = -limit;
     }
+}
+
+/// Sine and cosine from plain arithmetic. Unlike the libm backed `f64::sin_cos` it gives
+/// the same bits on every platform, which keeps seeded runs identical on native and wasm.
+pub fn sin_cos(angle: f64) -> (f64, f64) {
+    // pi / 2 split into two parts, so the reduction stays exact for moderate angles
+    const HALF_PI_HI: f64 = 1.5707963267341256;
+    const HALF_PI_LO: f64 = 6.077100506506192e-11;
+
+    let quadrant = (angle / FRAC_PI_2).round();
+    let x = angle - quadrant * HALF_PI_HI - quadrant * HALF_PI_LO;
+    let x2 = x * x;
+
+    let mut sin = 0.;
+    let mut cos = 0.;
+    for n in (1..=8).rev() {
+        sin = 1. - sin * x2 / ((2 * n) * (2 * n + 1)) as f64;
+        cos = 1. - cos * x2 / ((2 * n - 1) * (2 * n)) as f64;
+    }
+    let sin = sin * x;
+
+    match (quadrant as i64).rem_euclid(4) {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin)
+    }
+}
+
+/// Cube root by Newton's method, portable like `sin_cos`.
+pub fn cbrt(x: f64) -> f64 {
+    if x == 0. || !x.is_finite() {
+        return x;
+    }
+
+    // Dividing the exponent bits by three gives a guess within a few percent
+    let a = x.abs();
+    let mut y = f64::from_bits(a.to_bits() / 3 + 0x2A9F_7893_0000_0000);
+    for _ in 0..6 {
+        y -= (y - a / (y * y)) / 3.;
+    }
+    y.copysign(x)
+}
+
+/// Unit vector pointing at `angle` radians, see `sin_cos`.
+pub fn from_angle(angle: f32) -> Vec2 {
+    let (sin, cos) = sin_cos(angle as f64);
+    Vec2::new(cos as f32, sin as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sin_cos_matches_libm() {
+        for k in -1000..=1000 {
+            let angle = k as f64 * 0.0123;
+            let (sin, cos) = sin_cos(angle);
+            assert!((sin - angle.sin()).abs() < 1e-14 && (cos - angle.cos()).abs() < 1e-14, "{}", angle);
+        }
+    }
+
+    #[test]
+    fn cbrt_matches_libm() {
+        for x in [-27., -1e-300, 1e-9, 0.5, 2., 1e300] {
+            assert!((cbrt(x) / x.cbrt() - 1.).abs() < 1e-15, "{}", x);
+        }
+    }
 }
\ No newline at end of file